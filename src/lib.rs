@@ -43,19 +43,64 @@
 //! # }
 //! ```
 //!
+//! ### Closure-Based Access
+//!
+//! Short critical sections don't need guards at all. The lock is held only while
+//! the closure runs:
+//!
+//! ```rust
+//! use shared_container::{Shared, SyncAccess};
+//!
+//! let container = Shared::new(vec![1, 2, 3]);
+//!
+//! let len = container.with(|v| v.len()).unwrap();
+//! container.with_mut(|v| v.push(4)).unwrap();
+//! container.update(|v| v.iter().map(|x| x * 10).collect()).unwrap();
+//!
+//! let old = container.replace(vec![7]).unwrap();
+//! assert_eq!((len, old), (3, vec![10, 20, 30, 40]));
+//! assert_eq!(container.take().unwrap(), vec![7]);
+//! ```
+//!
+//! `AsyncAccess` offers the same operations as `with_async()`, `with_mut_async()`,
+//! `update_async()`, `replace_async()`, `take_async()` and `swap_async()`.
+//!
+//! On native targets `Shared<T>` implements `AsyncAccess` as well. It never blocks
+//! the executor: a waiting task is woken when the lock is released.
+//!
 //! ## Key Features
 //!
 //! - **Type-Level Separation**: `Shared<T>` for sync, `AsyncShared<T>` for async
+//! - **Mutex Containers**: `SharedMutex<T>` and `AsyncSharedMutex<T>` only need
+//!   `T: Send`
+//! - **Single-Threaded Containers**: `LocalShared<T>` is the wasm32 `Shared<T>`
+//!   backend on every target
+//! - **Thread-Bound Containers**: `ThreadBoundShared<T>` is `Send` for any `T` and
+//!   only grants access on its owner thread
+//! - **Single-Threaded Async**: `LocalAsyncShared<T>` for wasm32 and
+//!   `tokio::task::LocalSet`, with no `Send` bounds
 //! - **Platform-Aware**: Automatically uses the right backend based on target
-//!   - Native: `Arc<RwLock<T>>`
-//!   - WebAssembly: `Rc<RefCell<T>>`
+//!   - Native: `Arc` with a condvar-based reader-writer lock (supports timeouts)
+//!   - WebAssembly: `Rc` with a `RefCell`-style borrow flag
 //!   - Async: `Arc<tokio::sync::RwLock<T>>`
+//!   - Single-threaded async: `Rc` with a queued async borrow flag
 //! - **Explicit Errors**: `Result<_, AccessError>` instead of `Option` or panics
-//! - **Zero Runtime Overhead**: No blocking operations or runtime initialization
+//! - **No Runtime Required**: Async containers work with any executor, and nothing
+//!   is initialized at startup
 //!
 //! ## Feature Flags
 //!
-//! - **`async`**: Enables `AsyncShared<T>` and async trait methods (requires tokio)
+//! - **`async`**: Enables `AsyncShared<T>` and async trait methods. Only tokio's
+//!   runtime-independent `sync` primitives are used, so any executor works
+//! - **`tokio-rt`**: Lets the blocking methods of the async containers detect a tokio
+//!   runtime and return `AccessError::BlockingInRuntime` instead of blocking it
+//! - **`stream`**: Adds `AsyncShared::snapshots()`, a `futures_core::Stream` of values
+//! - **`async-cmp`**: Implements `PartialEq` and `PartialOrd` for `AsyncShared<T>` and
+//!   `AsyncSharedMutex<T>`, which block to read the values, and lets those of
+//!   `SharedAny<T>` compare its async variants
+//! - **`backend-parking-lot`**: Adds `ParkingLotBackend`, built on `parking_lot`
+//! - **`backend-mutex`**: Adds `MutexBackend`, built on `std::sync::Mutex`
+//! - **`backend-spin`**: Adds `SpinBackend`, a spin lock
 //! - **`std-sync`** (default): Legacy support for `SharedContainer` with std sync primitives
 //! - **`tokio-sync`**: Legacy support for `SharedContainer` with tokio primitives
 //! - **`wasm-sync`**: Legacy support for forcing WebAssembly backend
@@ -84,12 +129,126 @@
 //!     Err(AccessError::Poisoned) => println!("Lock was poisoned"),
 //!     Err(AccessError::BorrowConflict) => println!("Already borrowed"),
 //!     Err(AccessError::UnsupportedMode) => println!("Wrong container type"),
+//!     Err(AccessError::WouldBlock) => println!("Lock is busy"),
+//!     Err(AccessError::Timeout) => println!("Timed out waiting for lock"),
+//!     Err(AccessError::WrongThread) => println!("Accessed from another thread"),
+//!     Err(AccessError::BlockingInRuntime) => println!("Blocked inside a runtime"),
+//!     Err(AccessError::NotUnique) => println!("Other handles exist"),
+//!     Err(error) => println!("Access failed: {error}"),
 //! }
 //! ```
+//!
+//! ## Poisoning
+//!
+//! On native targets a panic while holding a write lock poisons a `Shared<T>`.
+//! `read_recoverable()` and `write_recoverable()` hand the guard back inside the
+//! error, `poison_info()` reports the panicking thread and where it acquired the
+//! lock, and `clear_poison()` makes the container usable again. A per-container
+//! [`PoisonPolicy`] can instead ignore poisoning or reset the value:
+//!
+//! ```rust
+//! use shared_container::{PoisonPolicy, Shared, SyncAccess};
+//!
+//! let sessions = Shared::with_poison_policy(Vec::<String>::new(), PoisonPolicy::Ignore);
+//! let other = sessions.clone();
+//! let _ = std::thread::spawn(move || {
+//!     let _guard = other.write().unwrap();
+//!     panic!("request handler failed");
+//! })
+//! .join();
+//!
+//! assert!(sessions.is_poisoned());
+//! assert!(sessions.read().is_ok());
+//! ```
+//!
+//! ## Non-Blocking Access
+//!
+//! `try_read()` and `try_write()` never wait for the lock. If the container is
+//! currently held in a conflicting mode they return `AccessError::WouldBlock`:
+//!
+//! ```rust
+//! use shared_container::{Shared, SyncAccess, AccessError};
+//!
+//! let container = Shared::new(42);
+//! let _writer = container.write().unwrap();
+//!
+//! // Skip the work instead of waiting for the writer
+//! assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+//! ```
+//!
+//! ## Timed Access
+//!
+//! `Shared<T>` can also wait for a bounded time with `read_timeout()`,
+//! `write_timeout()` and the deadline-based `read_until()` / `write_until()`.
+//! `AsyncShared<T>` offers `read_timeout_async()` and friends. All of them report
+//! `AccessError::Timeout` when the lock could not be acquired in time:
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use shared_container::{Shared, SyncAccess, AccessError};
+//!
+//! let container = Shared::new(42);
+//! let _writer = container.write().unwrap();
+//!
+//! let other = container.clone();
+//! let result = std::thread::spawn(move || {
+//!     other.read_timeout(Duration::from_millis(10)).map(|g| *g)
+//! })
+//! .join()
+//! .unwrap();
+//! assert_eq!(result, Err(AccessError::Timeout));
+//! ```
+//!
+//! ## Lock Backends
+//!
+//! `Shared<T, B = DefaultBackend>` takes the lock implementation as a type
+//! parameter. Besides the default, optional features provide `ParkingLotBackend`,
+//! `MutexBackend` (every mode exclusive) and `SpinBackend`, and any type
+//! implementing [`LockBackend`] can be plugged in. Poisoning, observers and
+//! `wait_until()` behave the same on every backend:
+//!
+//! ```rust
+//! # #[cfg(feature = "backend-spin")]
+//! # {
+//! use shared_container::{Shared, SpinBackend, SyncAccess};
+//!
+//! let hits: Shared<u64, SpinBackend> = Shared::with_backend(0);
+//! *hits.write().unwrap() += 1;
+//! assert_eq!(hits.get_cloned().unwrap(), 1);
+//! # }
+//! ```
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "async")]
+use std::borrow::Borrow;
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::time::{Duration, Instant};
+
+mod lock;
+
+#[cfg(feature = "backend-mutex")]
+pub use lock::MutexBackend;
+#[cfg(feature = "backend-parking-lot")]
+pub use lock::ParkingLotBackend;
+#[cfg(feature = "backend-spin")]
+pub use lock::SpinBackend;
+pub use lock::{DefaultBackend, LockBackend};
+
+// Reference counting for the multi-threaded legacy backends and the async containers
+#[cfg(any(
+    all(
+        feature = "std-sync",
+        not(feature = "tokio-sync"),
+        not(feature = "wasm-sync")
+    ),
+    feature = "tokio-sync",
+    feature = "async"
+))]
+use std::sync::{Arc, Weak};
 
 // Standard library synchronization primitives (default)
 #[cfg(all(
@@ -97,12 +256,10 @@ use std::ops::{Deref, DerefMut};
     not(feature = "tokio-sync"),
     not(feature = "wasm-sync")
 ))]
-use std::sync::{Arc, RwLock, Weak};
+use std::sync::RwLock;
 
 // Tokio async synchronization primitives
 #[cfg(feature = "tokio-sync")]
-use std::sync::{Arc, Weak};
-#[cfg(feature = "tokio-sync")]
 use tokio::sync::RwLock;
 
 // WebAssembly/single-threaded synchronization primitives
@@ -191,9 +348,11 @@ pub struct SharedContainer<T> {
 }
 
 // Implement Send and Sync for SharedContainer only for thread-safe implementations
+#[allow(deprecated)]
 #[cfg(any(feature = "std-sync", feature = "tokio-sync"))]
 unsafe impl<T: Send> Send for SharedContainer<T> {}
 
+#[allow(deprecated)]
 #[cfg(any(feature = "std-sync", feature = "tokio-sync"))]
 unsafe impl<T: Send + Sync> Sync for SharedContainer<T> {}
 
@@ -239,6 +398,7 @@ pub struct WeakSharedContainer<T> {
     wasm_inner: RcWeak<RefCell<T>>,
 }
 
+#[allow(deprecated)]
 impl<T> Clone for WeakSharedContainer<T> {
     fn clone(&self) -> Self {
         // Different implementations for different platforms
@@ -276,6 +436,7 @@ impl<T> Clone for WeakSharedContainer<T> {
     }
 }
 
+#[allow(deprecated)]
 impl<T: PartialEq> PartialEq for SharedContainer<T> {
     fn eq(&self, _other: &Self) -> bool {
         #[cfg(feature = "tokio-sync")]
@@ -296,6 +457,7 @@ impl<T: PartialEq> PartialEq for SharedContainer<T> {
     }
 }
 
+#[allow(deprecated)]
 impl<T> Clone for SharedContainer<T> {
     fn clone(&self) -> Self {
         #[cfg(all(
@@ -332,6 +494,7 @@ impl<T> Clone for SharedContainer<T> {
     }
 }
 
+#[allow(deprecated)]
 impl<T: Clone> SharedContainer<T> {
     /// Gets a clone of the contained value.
     ///
@@ -393,6 +556,7 @@ impl<T: Clone> SharedContainer<T> {
     }
 }
 
+#[allow(deprecated)]
 impl<T> SharedContainer<T> {
     /// Creates a new `SharedContainer` containing the given value.
     ///
@@ -625,6 +789,7 @@ impl<T> SharedContainer<T> {
     }
 }
 
+#[allow(deprecated)]
 impl<T> WeakSharedContainer<T> {
     /// Attempts to create a strong `SharedContainer` from this weak reference.
     ///
@@ -864,7 +1029,10 @@ impl<'a, T> DerefMut for SharedWriteGuard<'a, T> {
 // ============================================================================
 
 /// Errors that can occur when accessing shared containers.
+///
+/// New variants may be added in minor releases, so matches need a wildcard arm.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum AccessError {
     /// The requested operation is not supported for this container type.
     ///
@@ -882,6 +1050,35 @@ pub enum AccessError {
     ///
     /// This only occurs with multi-threaded RwLock-based containers.
     Poisoned,
+
+    /// The lock is currently held and acquiring it would require waiting.
+    ///
+    /// Returned by the non-blocking `try_*` methods instead of blocking the caller,
+    /// and by [`Shared::wait_until`] on wasm32 when the condition does not hold yet.
+    WouldBlock,
+
+    /// The lock could not be acquired before the timeout or deadline expired.
+    ///
+    /// Also returned by [`Shared::wait_until_timeout`] when the condition did not
+    /// hold in time.
+    Timeout,
+
+    /// The container was accessed from a thread other than its owner.
+    ///
+    /// Only [`ThreadBoundShared`] checks the calling thread.
+    WrongThread,
+
+    /// A blocking method was called from a thread that has entered a tokio runtime.
+    ///
+    /// Returned by [`AsyncShared::blocking_read`] and friends instead of stalling
+    /// the runtime; use the `*_async` methods there. Only tokio runtimes are
+    /// detected, and only with the `tokio-rt` feature.
+    BlockingInRuntime,
+
+    /// Other handles or weak references to the container exist.
+    ///
+    /// Returned by the `get_mut` methods, which need the only handle.
+    NotUnique,
 }
 
 impl std::fmt::Display for AccessError {
@@ -896,12 +1093,221 @@ impl std::fmt::Display for AccessError {
             AccessError::Poisoned => {
                 write!(f, "lock poisoned by panic")
             }
+            AccessError::WouldBlock => {
+                write!(f, "lock is busy: operation would block")
+            }
+            AccessError::Timeout => {
+                write!(f, "timed out waiting for lock")
+            }
+            AccessError::WrongThread => {
+                write!(f, "container accessed from a thread other than its owner")
+            }
+            AccessError::BlockingInRuntime => {
+                write!(f, "blocking access attempted from inside an async runtime")
+            }
+            AccessError::NotUnique => {
+                write!(f, "container has other handles")
+            }
         }
     }
 }
 
 impl std::error::Error for AccessError {}
 
+/// Describes the panic that poisoned a [`Shared`] container.
+///
+/// Poisoning only happens on native targets, so this is never produced on wasm32.
+#[derive(Debug, Clone)]
+pub struct PoisonInfo {
+    thread_id: std::thread::ThreadId,
+    thread_name: Option<String>,
+    location: &'static std::panic::Location<'static>,
+}
+
+impl PoisonInfo {
+    /// Records a panic on the current thread while holding a write lock acquired at
+    /// `location`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn current(location: &'static std::panic::Location<'static>) -> Self {
+        let thread = std::thread::current();
+        PoisonInfo {
+            thread_id: thread.id(),
+            thread_name: thread.name().map(str::to_owned),
+            location,
+        }
+    }
+
+    /// Returns the id of the thread that panicked.
+    pub fn thread_id(&self) -> std::thread::ThreadId {
+        self.thread_id
+    }
+
+    /// Returns the name of the thread that panicked, if it had one.
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    /// Returns where the write lock held during the panic was acquired.
+    pub fn location(&self) -> &'static std::panic::Location<'static> {
+        self.location
+    }
+}
+
+impl std::fmt::Display for PoisonInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.thread_name {
+            Some(name) => write!(f, "thread '{}'", name)?,
+            None => write!(f, "thread {:?}", self.thread_id)?,
+        }
+        write!(
+            f,
+            " panicked while holding a write lock acquired at {}",
+            self.location
+        )
+    }
+}
+
+/// A poisoned container's guard, returned so the data can still be accessed.
+///
+/// Returned inside [`RecoverableError::Poisoned`] by [`Shared::read_recoverable`] and
+/// [`Shared::write_recoverable`]. [`Shared::into_inner`] returns it holding the value
+/// itself.
+pub struct PoisonError<G> {
+    guard: G,
+    info: PoisonInfo,
+}
+
+impl<G> PoisonError<G> {
+    /// Consumes the error, returning the guard or value.
+    pub fn into_inner(self) -> G {
+        self.guard
+    }
+
+    /// Returns a reference to the guard.
+    pub fn get_ref(&self) -> &G {
+        &self.guard
+    }
+
+    /// Returns a mutable reference to the guard.
+    pub fn get_mut(&mut self) -> &mut G {
+        &mut self.guard
+    }
+
+    /// Describes the panic that poisoned the container.
+    pub fn info(&self) -> &PoisonInfo {
+        &self.info
+    }
+}
+
+impl<G> std::fmt::Debug for PoisonError<G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PoisonError")
+            .field("info", &self.info)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<G> std::fmt::Display for PoisonError<G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "lock poisoned by panic: {}", self.info)
+    }
+}
+
+impl<G> std::error::Error for PoisonError<G> {}
+
+/// Errors returned by the recoverable access methods of [`Shared`].
+///
+/// Converts into [`AccessError`], so `?` works in functions returning `AccessError`.
+pub enum RecoverableError<G> {
+    /// The container is poisoned. The error still holds the guard.
+    Poisoned(PoisonError<G>),
+
+    /// The lock could not be acquired.
+    Access(AccessError),
+}
+
+impl<G> From<RecoverableError<G>> for AccessError {
+    fn from(err: RecoverableError<G>) -> Self {
+        match err {
+            RecoverableError::Poisoned(_) => AccessError::Poisoned,
+            RecoverableError::Access(err) => err,
+        }
+    }
+}
+
+impl<G> std::fmt::Debug for RecoverableError<G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecoverableError::Poisoned(err) => f.debug_tuple("Poisoned").field(err).finish(),
+            RecoverableError::Access(err) => f.debug_tuple("Access").field(err).finish(),
+        }
+    }
+}
+
+impl<G> std::fmt::Display for RecoverableError<G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecoverableError::Poisoned(err) => err.fmt(f),
+            RecoverableError::Access(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<G> std::error::Error for RecoverableError<G> {}
+
+/// What a [`Shared`] container does once a panic has poisoned it.
+///
+/// Set with [`Shared::with_poison_policy`]. Poisoning only happens on native
+/// targets, so the policy has no effect on wasm32.
+#[derive(Default)]
+pub enum PoisonPolicy<T> {
+    /// Report `AccessError::Poisoned` until [`Shared::clear_poison`] is called.
+    ///
+    /// This is the default.
+    #[default]
+    Propagate,
+
+    /// Hand out guards as if the container were not poisoned.
+    ///
+    /// [`Shared::is_poisoned`] still reports the poisoning.
+    Ignore,
+
+    /// Replace the value with the factory's output when the panicking writer
+    /// releases the lock, leaving the container unpoisoned.
+    ///
+    /// The factory runs, and the old value is dropped, while the panicking thread
+    /// unwinds. Neither may panic, as that aborts the process.
+    Reset(Box<dyn Fn() -> T + Send + Sync>),
+}
+
+impl<T> PoisonPolicy<T> {
+    /// Resets the value to the output of `factory` after a panic.
+    pub fn reset_with<F>(factory: F) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        PoisonPolicy::Reset(Box::new(factory))
+    }
+
+    /// Resets the value to `T::default()` after a panic.
+    pub fn reset_to_default() -> Self
+    where
+        T: Default + 'static,
+    {
+        PoisonPolicy::Reset(Box::new(T::default))
+    }
+}
+
+impl<T> std::fmt::Debug for PoisonPolicy<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoisonPolicy::Propagate => f.write_str("Propagate"),
+            PoisonPolicy::Ignore => f.write_str("Ignore"),
+            PoisonPolicy::Reset(_) => f.write_str("Reset(..)"),
+        }
+    }
+}
+
 /// Trait for synchronous access to shared containers.
 pub trait SyncAccess<T> {
     /// Acquires a read lock on the container.
@@ -910,10 +1316,95 @@ pub trait SyncAccess<T> {
     /// Acquires a write lock on the container.
     fn write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError>;
 
+    /// Attempts to acquire a read lock without blocking.
+    ///
+    /// Returns `AccessError::WouldBlock` if a writer currently holds the lock. The
+    /// default implementation returns `AccessError::UnsupportedMode`.
+    fn try_read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        Err(AccessError::UnsupportedMode)
+    }
+
+    /// Attempts to acquire a write lock without blocking.
+    ///
+    /// Returns `AccessError::WouldBlock` if any reader or writer currently holds the
+    /// lock. The default implementation returns `AccessError::UnsupportedMode`.
+    fn try_write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        Err(AccessError::UnsupportedMode)
+    }
+
     /// Gets a clone of the contained value.
     fn get_cloned(&self) -> Result<T, AccessError>
     where
         T: Clone;
+
+    /// Runs `f` with shared access to the value and returns its result.
+    ///
+    /// The read lock is held only for the duration of the call.
+    fn with<R, F>(&self, f: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> R,
+        Self: Sized,
+    {
+        let guard = self.read()?;
+        Ok(f(&guard))
+    }
+
+    /// Runs `f` with exclusive access to the value and returns its result.
+    ///
+    /// The write lock is held only for the duration of the call.
+    #[track_caller]
+    fn with_mut<R, F>(&self, f: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&mut T) -> R,
+        Self: Sized,
+    {
+        let mut guard = self.write()?;
+        Ok(f(&mut guard))
+    }
+
+    /// Replaces the value with the result of `f` applied to the current value.
+    #[track_caller]
+    fn update<F>(&self, f: F) -> Result<(), AccessError>
+    where
+        F: FnOnce(&T) -> T,
+        Self: Sized,
+    {
+        let mut guard = self.write()?;
+        *guard = f(&guard);
+        Ok(())
+    }
+
+    /// Replaces the value, returning the old one.
+    #[track_caller]
+    fn replace(&self, value: T) -> Result<T, AccessError> {
+        let mut guard = self.write()?;
+        Ok(std::mem::replace(&mut *guard, value))
+    }
+
+    /// Takes the value, leaving `T::default()` in its place.
+    #[track_caller]
+    fn take(&self) -> Result<T, AccessError>
+    where
+        T: Default,
+    {
+        let mut guard = self.write()?;
+        Ok(std::mem::take(&mut *guard))
+    }
+
+    /// Swaps the values of two containers.
+    ///
+    /// Swapping a container with itself (or a clone of itself) is a no-op. Locks are
+    /// taken in a consistent order, so concurrent swaps of the same pair cannot deadlock.
+    ///
+    /// The default implementation returns `AccessError::UnsupportedMode`, since
+    /// without knowing whether both handles share a lock it could deadlock.
+    fn swap(&self, other: &Self) -> Result<(), AccessError>
+    where
+        Self: Sized,
+    {
+        let _ = other;
+        Err(AccessError::UnsupportedMode)
+    }
 }
 
 /// Trait for asynchronous access to shared containers.
@@ -935,281 +1426,6407 @@ pub trait AsyncAccess<T> {
     fn get_cloned_async(&self) -> impl std::future::Future<Output = T> + Send
     where
         T: Clone;
-}
 
-/// Read guard for synchronous access.
-#[derive(Debug)]
-pub enum SyncReadGuard<'a, T> {
-    #[cfg(not(target_arch = "wasm32"))]
-    Std(std::sync::RwLockReadGuard<'a, T>),
-    #[cfg(target_arch = "wasm32")]
-    Wasm(Ref<'a, T>),
-}
+    /// Attempts to acquire a read lock without waiting.
+    ///
+    /// This method does not suspend; it returns `AccessError::WouldBlock` if a
+    /// writer currently holds the lock. The default implementation returns
+    /// `AccessError::UnsupportedMode`.
+    fn try_read_async(&self) -> Result<AsyncReadGuard<'_, T>, AccessError> {
+        Err(AccessError::UnsupportedMode)
+    }
 
-impl<'a, T> Deref for SyncReadGuard<'a, T> {
-    type Target = T;
+    /// Attempts to acquire a write lock without waiting.
+    ///
+    /// This method does not suspend; it returns `AccessError::WouldBlock` if any
+    /// reader or writer currently holds the lock. The default implementation returns
+    /// `AccessError::UnsupportedMode`.
+    fn try_write_async(&self) -> Result<AsyncWriteGuard<'_, T>, AccessError> {
+        Err(AccessError::UnsupportedMode)
+    }
 
-    fn deref(&self) -> &Self::Target {
-        match self {
-            #[cfg(not(target_arch = "wasm32"))]
-            SyncReadGuard::Std(guard) => guard.deref(),
-            #[cfg(target_arch = "wasm32")]
-            SyncReadGuard::Wasm(guard) => guard.deref(),
+    /// Runs `f` with shared access to the value and returns its result.
+    ///
+    /// The read lock is held only for the duration of the call, so no guard can be
+    /// held across an `.await` by accident.
+    fn with_async<'a, R, F>(&'a self, f: F) -> impl std::future::Future<Output = R> + Send + 'a
+    where
+        F: FnOnce(&T) -> R + Send + 'a,
+        T: 'a,
+    {
+        let read = self.read_async();
+        async move {
+            let guard = read.await;
+            f(&guard)
         }
     }
-}
 
-/// Write guard for synchronous access.
-#[derive(Debug)]
-pub enum SyncWriteGuard<'a, T> {
-    #[cfg(not(target_arch = "wasm32"))]
-    Std(std::sync::RwLockWriteGuard<'a, T>),
-    #[cfg(target_arch = "wasm32")]
-    Wasm(RefMut<'a, T>),
-}
+    /// Runs `f` with exclusive access to the value and returns its result.
+    ///
+    /// The write lock is held only for the duration of the call.
+    fn with_mut_async<'a, R, F>(&'a self, f: F) -> impl std::future::Future<Output = R> + Send + 'a
+    where
+        F: FnOnce(&mut T) -> R + Send + 'a,
+        T: 'a,
+    {
+        let write = self.write_async();
+        async move {
+            let mut guard = write.await;
+            f(&mut guard)
+        }
+    }
 
-impl<'a, T> Deref for SyncWriteGuard<'a, T> {
-    type Target = T;
+    /// Replaces the value with the result of `f` applied to the current value.
+    fn update_async<'a, F>(&'a self, f: F) -> impl std::future::Future<Output = ()> + Send + 'a
+    where
+        F: FnOnce(&T) -> T + Send + 'a,
+        T: 'a,
+    {
+        let write = self.write_async();
+        async move {
+            let mut guard = write.await;
+            *guard = f(&guard);
+        }
+    }
 
-    fn deref(&self) -> &Self::Target {
-        match self {
-            #[cfg(not(target_arch = "wasm32"))]
-            SyncWriteGuard::Std(guard) => guard.deref(),
-            #[cfg(target_arch = "wasm32")]
-            SyncWriteGuard::Wasm(guard) => guard.deref(),
+    /// Replaces the value, returning the old one.
+    fn replace_async<'a>(&'a self, value: T) -> impl std::future::Future<Output = T> + Send + 'a
+    where
+        T: Send + 'a,
+    {
+        let write = self.write_async();
+        async move {
+            let mut guard = write.await;
+            std::mem::replace(&mut *guard, value)
         }
     }
-}
 
-impl<'a, T> DerefMut for SyncWriteGuard<'a, T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        match self {
-            #[cfg(not(target_arch = "wasm32"))]
-            SyncWriteGuard::Std(guard) => guard.deref_mut(),
-            #[cfg(target_arch = "wasm32")]
-            SyncWriteGuard::Wasm(guard) => guard.deref_mut(),
+    /// Takes the value, leaving `T::default()` in its place.
+    fn take_async<'a>(&'a self) -> impl std::future::Future<Output = T> + Send + 'a
+    where
+        T: Default + 'a,
+    {
+        let write = self.write_async();
+        async move {
+            let mut guard = write.await;
+            std::mem::take(&mut *guard)
+        }
+    }
+
+    /// Swaps the values of two containers.
+    ///
+    /// Swapping a container with itself (or a clone of itself) is a no-op. Locks are
+    /// taken in a consistent order, so concurrent swaps of the same pair cannot deadlock.
+    ///
+    /// The default implementation only recognises `other` as the same handle as
+    /// `self`, and orders the locks by handle address. Implementations whose clones
+    /// share one value should override it, or swapping two clones waits forever.
+    fn swap_async<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> impl std::future::Future<Output = ()> + Send + 'a
+    where
+        T: Send + Sync + 'a,
+        Self: Sized,
+    {
+        let same = std::ptr::eq(self, other);
+        let (first, second) = if std::ptr::from_ref(self) < std::ptr::from_ref(other) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let (first, second) = (first.write_async(), second.write_async());
+        async move {
+            if same {
+                return;
+            }
+            let mut first = first.await;
+            let mut second = second.await;
+            std::mem::swap(&mut *first, &mut *second);
         }
     }
 }
 
-/// Read guard for asynchronous access.
-#[cfg(feature = "async")]
-#[derive(Debug)]
-pub struct AsyncReadGuard<'a, T>(tokio::sync::RwLockReadGuard<'a, T>);
+/// Trait for asynchronous access to single-threaded containers.
+///
+/// The counterpart of `AsyncAccess` for containers such as [`LocalAsyncShared`]:
+/// the futures are not `Send`, so neither the container nor the value has to be.
+/// That suits wasm32 and `tokio::task::LocalSet`.
+pub trait LocalAsyncAccess<T> {
+    /// Asynchronously acquires a read lock on the container.
+    fn read_async<'a>(&'a self) -> impl std::future::Future<Output = LocalAsyncReadGuard<'a, T>>
+    where
+        T: 'a;
 
+    /// Asynchronously acquires a write lock on the container.
+    fn write_async<'a>(&'a self) -> impl std::future::Future<Output = LocalAsyncWriteGuard<'a, T>>
+    where
+        T: 'a;
+
+    /// Attempts to acquire a read lock without waiting.
+    ///
+    /// Returns `AccessError::WouldBlock` if a writer holds the lock or tasks are
+    /// already waiting for it.
+    fn try_read_async(&self) -> Result<LocalAsyncReadGuard<'_, T>, AccessError>;
+
+    /// Attempts to acquire a write lock without waiting.
+    ///
+    /// Returns `AccessError::WouldBlock` if the lock is held or tasks are already
+    /// waiting for it.
+    fn try_write_async(&self) -> Result<LocalAsyncWriteGuard<'_, T>, AccessError>;
+
+    /// Asynchronously gets a clone of the contained value.
+    fn get_cloned_async(&self) -> impl std::future::Future<Output = T>
+    where
+        T: Clone,
+    {
+        async move { (*self.read_async().await).clone() }
+    }
+
+    /// Runs `f` with shared access to the value and returns its result.
+    ///
+    /// The read lock is held only for the duration of the call.
+    fn with_async<'a, R, F>(&'a self, f: F) -> impl std::future::Future<Output = R> + 'a
+    where
+        F: FnOnce(&T) -> R + 'a,
+        T: 'a,
+    {
+        async move {
+            let guard = self.read_async().await;
+            f(&guard)
+        }
+    }
+
+    /// Runs `f` with exclusive access to the value and returns its result.
+    ///
+    /// The write lock is held only for the duration of the call.
+    fn with_mut_async<'a, R, F>(&'a self, f: F) -> impl std::future::Future<Output = R> + 'a
+    where
+        F: FnOnce(&mut T) -> R + 'a,
+        T: 'a,
+    {
+        async move {
+            let mut guard = self.write_async().await;
+            f(&mut guard)
+        }
+    }
+
+    /// Replaces the value with the result of `f` applied to the current value.
+    fn update_async<'a, F>(&'a self, f: F) -> impl std::future::Future<Output = ()> + 'a
+    where
+        F: FnOnce(&T) -> T + 'a,
+        T: 'a,
+    {
+        async move {
+            let mut guard = self.write_async().await;
+            *guard = f(&guard);
+        }
+    }
+
+    /// Replaces the value, returning the old one.
+    fn replace_async<'a>(&'a self, value: T) -> impl std::future::Future<Output = T> + 'a
+    where
+        T: 'a,
+    {
+        async move {
+            let mut guard = self.write_async().await;
+            std::mem::replace(&mut *guard, value)
+        }
+    }
+
+    /// Takes the value, leaving `T::default()` in its place.
+    fn take_async<'a>(&'a self) -> impl std::future::Future<Output = T> + 'a
+    where
+        T: Default + 'a,
+    {
+        async move {
+            let mut guard = self.write_async().await;
+            std::mem::take(&mut *guard)
+        }
+    }
+
+    /// Swaps the values of two containers.
+    ///
+    /// Swapping a container with itself (or a clone of itself) is a no-op. Locks are
+    /// taken in a consistent order, so concurrent swaps of the same pair cannot deadlock.
+    ///
+    /// The default implementation behaves like [`AsyncAccess::swap_async`]'s: it only
+    /// recognises the same handle, so implementations whose clones share one value
+    /// should override it.
+    fn swap_async<'a>(&'a self, other: &'a Self) -> impl std::future::Future<Output = ()> + 'a
+    where
+        T: 'a,
+        Self: Sized,
+    {
+        let same = std::ptr::eq(self, other);
+        let (first, second) = if std::ptr::from_ref(self) < std::ptr::from_ref(other) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        async move {
+            if same {
+                return;
+            }
+            let mut first = first.write_async().await;
+            let mut second = second.write_async().await;
+            std::mem::swap(&mut *first, &mut *second);
+        }
+    }
+}
+
+/// The parts of a container that guards need once mapping has erased the value and
+/// backend types.
+trait LockTarget {
+    fn backend(&self) -> &dyn LockBackend;
+
+    fn holders(&self) -> &lock::Holders;
+
+    /// Records that the holder of the exclusive lock panicked.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn poison(&self, info: PoisonInfo);
+
+    /// Releases the exclusive lock on behalf of its last holder.
+    fn release_exclusive(&self);
+
+    /// Turns the exclusive lock into a shared lock, waking threads that wait for
+    /// writes.
+    fn downgrade(&self);
+
+    /// Notifies observers of a write that just ended. The caller holds the lock in
+    /// shared mode.
+    fn written(&self);
+}
+
+/// Gives up one hold on `target`'s shared lock, unlocking the backend if it was the
+/// last one.
+fn release_shared(target: &dyn LockTarget) {
+    if target.holders().leave_shared() {
+        target.backend().unlock_shared();
+    }
+}
+
+/// The lock behind a sync guard.
+enum HeldLock<'a> {
+    /// A raw lock, released through its target.
+    Target(&'a dyn LockTarget),
+    /// The guard of an async container, which has no raw lock. Guards split from one
+    /// another share it, and the lock is released with the last of them.
+    #[cfg(feature = "async")]
+    Guard(std::rc::Rc<dyn GuardLock + 'a>),
+}
+
+impl<'a> HeldLock<'a> {
+    /// Returns the target of a raw lock.
+    fn target(&self) -> Option<&'a dyn LockTarget> {
+        match self {
+            HeldLock::Target(target) => Some(*target),
+            #[cfg(feature = "async")]
+            HeldLock::Guard(_) => None,
+        }
+    }
+}
+
+/// A lock held through the guard of an async container.
 #[cfg(feature = "async")]
-impl<'a, T> Deref for AsyncReadGuard<'a, T> {
+trait GuardLock {
+    /// Turns the held write lock into a read lock.
+    fn downgrade(&self);
+}
+
+/// Releases a shared lock when dropped.
+struct ReadRelease<'a> {
+    lock: HeldLock<'a>,
+}
+
+impl<'a> ReadRelease<'a> {
+    /// Registers another holder of the same shared lock.
+    fn split(&self) -> Self {
+        let lock = match &self.lock {
+            HeldLock::Target(target) => {
+                target.holders().share_shared();
+                HeldLock::Target(*target)
+            }
+            #[cfg(feature = "async")]
+            HeldLock::Guard(guard) => HeldLock::Guard(guard.clone()),
+        };
+        ReadRelease { lock }
+    }
+
+    /// Gives up responsibility for a raw lock without releasing it.
+    fn into_target(self) -> &'a dyn LockTarget {
+        let target = self.lock.target().expect("only raw locks are handed over");
+        std::mem::forget(self);
+        target
+    }
+}
+
+impl Drop for ReadRelease<'_> {
+    fn drop(&mut self) {
+        if let Some(target) = self.lock.target() {
+            release_shared(target);
+        }
+    }
+}
+
+/// Where and how a write lock was acquired, for poisoning on native targets.
+#[derive(Clone, Copy)]
+struct WriteOrigin {
+    #[cfg(not(target_arch = "wasm32"))]
+    panicking: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    location: &'static std::panic::Location<'static>,
+}
+
+impl WriteOrigin {
+    #[track_caller]
+    fn current() -> Self {
+        WriteOrigin {
+            #[cfg(not(target_arch = "wasm32"))]
+            panicking: std::thread::panicking(),
+            #[cfg(not(target_arch = "wasm32"))]
+            location: std::panic::Location::caller(),
+        }
+    }
+
+    /// Gives up one hold on `target`'s exclusive lock, poisoning it if the holder
+    /// panicked.
+    fn release(self, target: &dyn LockTarget) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if !self.panicking && std::thread::panicking() {
+            target.poison(PoisonInfo::current(self.location));
+        }
+        if target.holders().leave_exclusive() {
+            target.release_exclusive();
+        }
+    }
+}
+
+/// Releases an exclusive lock when dropped, poisoning it on native targets if the
+/// holder panicked.
+struct WriteRelease<'a> {
+    lock: HeldLock<'a>,
+    origin: WriteOrigin,
+}
+
+impl<'a> WriteRelease<'a> {
+    /// Registers another holder of the same exclusive lock.
+    fn split(&self) -> Self {
+        let lock = match &self.lock {
+            HeldLock::Target(target) => {
+                target.holders().share_exclusive();
+                HeldLock::Target(*target)
+            }
+            #[cfg(feature = "async")]
+            HeldLock::Guard(guard) => HeldLock::Guard(guard.clone()),
+        };
+        WriteRelease {
+            lock,
+            origin: self.origin,
+        }
+    }
+
+    /// Gives up responsibility for a raw lock without releasing it.
+    fn into_target(self) -> &'a dyn LockTarget {
+        let target = self.lock.target().expect("only raw locks are handed over");
+        std::mem::forget(self);
+        target
+    }
+
+    /// Turns the exclusive lock into a shared lock. Observers of raw locks are
+    /// left for the caller to notify.
+    fn downgrade(self) -> ReadRelease<'a> {
+        let this = std::mem::ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again.
+        let lock = unsafe { std::ptr::read(&this.lock) };
+        match &lock {
+            HeldLock::Target(target) => target.downgrade(),
+            #[cfg(feature = "async")]
+            HeldLock::Guard(guard) => guard.downgrade(),
+        }
+        ReadRelease { lock }
+    }
+}
+
+impl Drop for WriteRelease<'_> {
+    fn drop(&mut self) {
+        if let Some(target) = self.lock.target() {
+            self.origin.release(target);
+        }
+    }
+}
+
+/// Releases an upgradable lock when dropped.
+struct UpgradableRelease<'a> {
+    target: &'a dyn LockTarget,
+}
+
+impl<'a> UpgradableRelease<'a> {
+    /// Gives up responsibility for the lock without releasing it.
+    fn into_target(self) -> &'a dyn LockTarget {
+        let target = self.target;
+        std::mem::forget(self);
+        target
+    }
+}
+
+impl Drop for UpgradableRelease<'_> {
+    fn drop(&mut self) {
+        self.target.backend().unlock_upgradable();
+    }
+}
+
+/// Releases a shared lock when dropped, keeping its container alive until then.
+struct OwnedReadRelease<T, B: LockBackend> {
+    shared: Shared<T, B>,
+}
+
+impl<T, B: LockBackend> OwnedReadRelease<T, B> {
+    /// Registers another holder of the same shared lock.
+    fn split(&self) -> Self {
+        self.shared.inner.holders.share_shared();
+        OwnedReadRelease {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T, B: LockBackend> Drop for OwnedReadRelease<T, B> {
+    fn drop(&mut self) {
+        release_shared(&*self.shared.inner);
+    }
+}
+
+/// Releases an exclusive lock when dropped, keeping its container alive until then.
+///
+/// Poisons the lock on native targets if the holder panicked.
+struct OwnedWriteRelease<T, B: LockBackend> {
+    shared: Shared<T, B>,
+    origin: WriteOrigin,
+}
+
+impl<T, B: LockBackend> OwnedWriteRelease<T, B> {
+    /// Registers another holder of the same exclusive lock.
+    fn split(&self) -> Self {
+        self.shared.inner.holders.share_exclusive();
+        OwnedWriteRelease {
+            shared: self.shared.clone(),
+            origin: self.origin,
+        }
+    }
+
+    /// Gives up responsibility for the lock without releasing it.
+    fn into_shared(self) -> Shared<T, B> {
+        let this = std::mem::ManuallyDrop::new(self);
+        // SAFETY: `this` is never used or dropped again.
+        unsafe { std::ptr::read(&this.shared) }
+    }
+}
+
+impl<T, B: LockBackend> Drop for OwnedWriteRelease<T, B> {
+    fn drop(&mut self) {
+        self.origin.release(&*self.shared.inner);
+    }
+}
+
+/// Read guard for synchronous access.
+///
+/// Releases the read lock when dropped. Use [`SyncReadGuard::map`] to narrow the
+/// guard to a part of the locked data while keeping the lock held.
+pub struct SyncReadGuard<'a, T: ?Sized> {
+    data: NonNull<T>,
+    release: ReadRelease<'a>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: ?Sized> SyncReadGuard<'a, T> {
+    /// Wraps a read lock that the caller has already acquired.
+    ///
+    /// # Safety
+    /// The caller must hold a shared lock on `target` that protects `data`.
+    unsafe fn new(target: &'a dyn LockTarget, data: *mut T) -> Self {
+        SyncReadGuard {
+            // SAFETY: `data` points into a live container allocation.
+            data: unsafe { NonNull::new_unchecked(data) },
+            release: ReadRelease {
+                lock: HeldLock::Target(target),
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// The read lock stays held until the returned guard is dropped. This is an
+    /// associated function so it does not shadow methods of `T`; call it as
+    /// `SyncReadGuard::map(guard, |v| &v.field)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{Shared, SyncAccess, SyncReadGuard};
+    ///
+    /// struct Config { name: String, retries: u32 }
+    ///
+    /// let config = Shared::new(Config { name: "db".into(), retries: 3 });
+    /// let name = SyncReadGuard::map(config.read().unwrap(), |c| c.name.as_str());
+    /// assert_eq!(&*name, "db");
+    /// ```
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> SyncReadGuard<'a, U>
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        let data = NonNull::from(f(&*this));
+        let SyncReadGuard { release, .. } = this;
+        SyncReadGuard {
+            data,
+            release,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<U: ?Sized, F>(this: Self, f: F) -> Result<SyncReadGuard<'a, U>, Self>
+    where
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        match f(&*this).map(NonNull::from) {
+            Some(data) => {
+                let SyncReadGuard { release, .. } = this;
+                Ok(SyncReadGuard {
+                    data,
+                    release,
+                    _marker: PhantomData,
+                })
+            }
+            None => Err(this),
+        }
+    }
+
+    /// Splits the guard into two guards for different components of the locked data.
+    ///
+    /// The read lock stays held until both returned guards are dropped.
+    pub fn map_split<U: ?Sized, V: ?Sized, F>(
+        this: Self,
+        f: F,
+    ) -> (SyncReadGuard<'a, U>, SyncReadGuard<'a, V>)
+    where
+        F: FnOnce(&T) -> (&U, &V),
+    {
+        let (first, second) = f(&*this);
+        let (first, second) = (NonNull::from(first), NonNull::from(second));
+        let SyncReadGuard { release, .. } = this;
+        (
+            SyncReadGuard {
+                data: first,
+                release: release.split(),
+                _marker: PhantomData,
+            },
+            SyncReadGuard {
+                data: second,
+                release,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<T: ?Sized> Deref for SyncReadGuard<'_, T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.0.deref()
+        // SAFETY: the shared lock is held for the lifetime of the guard.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for SyncReadGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+// SAFETY: the guard only hands out `&T`.
+unsafe impl<T: ?Sized + Sync> Sync for SyncReadGuard<'_, T> {}
+
+/// Write guard for synchronous access.
+///
+/// Releases the write lock when dropped. On native targets, dropping the guard
+/// while the thread is panicking poisons the container. Use [`SyncWriteGuard::map`]
+/// to narrow the guard to a part of the locked data.
+pub struct SyncWriteGuard<'a, T: ?Sized> {
+    data: NonNull<T>,
+    release: WriteRelease<'a>,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: ?Sized> SyncWriteGuard<'a, T> {
+    /// Wraps a write lock that the caller has already acquired.
+    ///
+    /// # Safety
+    /// The caller must hold the exclusive lock on `target` that protects `data`.
+    #[track_caller]
+    unsafe fn new(target: &'a dyn LockTarget, data: *mut T) -> Self {
+        SyncWriteGuard {
+            // SAFETY: `data` points into a live container allocation.
+            data: unsafe { NonNull::new_unchecked(data) },
+            release: WriteRelease {
+                lock: HeldLock::Target(target),
+                origin: WriteOrigin::current(),
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// The write lock stays held until the returned guard is dropped. This is an
+    /// associated function so it does not shadow methods of `T`; call it as
+    /// `SyncWriteGuard::map(guard, |v| &mut v.field)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{Shared, SyncAccess, SyncWriteGuard};
+    ///
+    /// let pair = Shared::new((1, String::from("a")));
+    /// let mut text = SyncWriteGuard::map(pair.write().unwrap(), |p| &mut p.1);
+    /// text.push('b');
+    /// drop(text);
+    /// assert_eq!(pair.get_cloned().unwrap().1, "ab");
+    /// ```
+    pub fn map<U: ?Sized, F>(mut this: Self, f: F) -> SyncMappedWriteGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let data = NonNull::from(f(&mut *this));
+        let SyncWriteGuard { release, .. } = this;
+        SyncMappedWriteGuard {
+            data,
+            release,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<U: ?Sized, F>(mut this: Self, f: F) -> Result<SyncMappedWriteGuard<'a, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match f(&mut *this).map(NonNull::from) {
+            Some(data) => {
+                let SyncWriteGuard { release, .. } = this;
+                Ok(SyncMappedWriteGuard {
+                    data,
+                    release,
+                    _marker: PhantomData,
+                })
+            }
+            None => Err(this),
+        }
+    }
+
+    /// Splits the guard into two guards for disjoint components of the locked data.
+    ///
+    /// The write lock stays held until both returned guards are dropped.
+    pub fn map_split<U: ?Sized, V: ?Sized, F>(
+        mut this: Self,
+        f: F,
+    ) -> (SyncMappedWriteGuard<'a, U>, SyncMappedWriteGuard<'a, V>)
+    where
+        F: FnOnce(&mut T) -> (&mut U, &mut V),
+    {
+        let (first, second) = f(&mut *this);
+        let (first, second) = (NonNull::from(first), NonNull::from(second));
+        let SyncWriteGuard { release, .. } = this;
+        (
+            SyncMappedWriteGuard {
+                data: first,
+                release: release.split(),
+                _marker: PhantomData,
+            },
+            SyncMappedWriteGuard {
+                data: second,
+                release,
+                _marker: PhantomData,
+            },
+        )
+    }
+
+    /// Atomically turns the write guard into a read guard.
+    ///
+    /// Other readers may proceed once this returns, but no writer can acquire the
+    /// lock in between, so the data read through the returned guard is exactly what
+    /// this guard left behind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{Shared, SyncAccess, SyncWriteGuard};
+    ///
+    /// let counter = Shared::new(0);
+    /// let mut guard = counter.write().unwrap();
+    /// *guard += 1;
+    /// let guard = SyncWriteGuard::downgrade(guard);
+    /// assert_eq!(*guard, 1);
+    /// assert!(counter.try_read().is_ok());
+    /// ```
+    pub fn downgrade(this: Self) -> SyncReadGuard<'a, T> {
+        let SyncWriteGuard { data, release, .. } = this;
+        let guard = SyncReadGuard {
+            data,
+            release: release.downgrade(),
+            _marker: PhantomData,
+        };
+        if let Some(target) = guard.release.lock.target() {
+            target.written();
+        }
+        guard
+    }
+}
+
+impl<T: ?Sized> Deref for SyncWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the exclusive lock is held for the lifetime of the guard.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for SyncWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: the exclusive lock is held for the lifetime of the guard.
+        unsafe { self.data.as_mut() }
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for SyncWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+// SAFETY: sharing the guard only hands out `&T`.
+unsafe impl<T: ?Sized + Sync> Sync for SyncWriteGuard<'_, T> {}
+
+/// Write guard for a component of the data locked by a [`SyncWriteGuard`].
+///
+/// Created by [`SyncWriteGuard::map`], [`SyncWriteGuard::try_map`] and
+/// [`SyncWriteGuard::map_split`]. The write lock is released once every guard
+/// produced from the original one has been dropped.
+pub struct SyncMappedWriteGuard<'a, T: ?Sized> {
+    data: NonNull<T>,
+    release: WriteRelease<'a>,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: ?Sized> SyncMappedWriteGuard<'a, T> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// See [`SyncWriteGuard::map`].
+    pub fn map<U: ?Sized, F>(mut this: Self, f: F) -> SyncMappedWriteGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let data = NonNull::from(f(&mut *this));
+        let SyncMappedWriteGuard { release, .. } = this;
+        SyncMappedWriteGuard {
+            data,
+            release,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<U: ?Sized, F>(mut this: Self, f: F) -> Result<SyncMappedWriteGuard<'a, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match f(&mut *this).map(NonNull::from) {
+            Some(data) => {
+                let SyncMappedWriteGuard { release, .. } = this;
+                Ok(SyncMappedWriteGuard {
+                    data,
+                    release,
+                    _marker: PhantomData,
+                })
+            }
+            None => Err(this),
+        }
+    }
+
+    /// Splits the guard into two guards for disjoint components of the locked data.
+    ///
+    /// See [`SyncWriteGuard::map_split`].
+    pub fn map_split<U: ?Sized, V: ?Sized, F>(
+        mut this: Self,
+        f: F,
+    ) -> (SyncMappedWriteGuard<'a, U>, SyncMappedWriteGuard<'a, V>)
+    where
+        F: FnOnce(&mut T) -> (&mut U, &mut V),
+    {
+        let (first, second) = f(&mut *this);
+        let (first, second) = (NonNull::from(first), NonNull::from(second));
+        let SyncMappedWriteGuard { release, .. } = this;
+        (
+            SyncMappedWriteGuard {
+                data: first,
+                release: release.split(),
+                _marker: PhantomData,
+            },
+            SyncMappedWriteGuard {
+                data: second,
+                release,
+                _marker: PhantomData,
+            },
+        )
+    }
+}
+
+impl<T: ?Sized> Deref for SyncMappedWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the exclusive lock is held for the lifetime of the guard.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for SyncMappedWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: the exclusive lock is held for the lifetime of the guard.
+        unsafe { self.data.as_mut() }
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for SyncMappedWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+// SAFETY: sharing the guard only hands out `&T`.
+unsafe impl<T: ?Sized + Sync> Sync for SyncMappedWriteGuard<'_, T> {}
+
+/// Upgradable read guard for synchronous access.
+///
+/// Created by [`Shared::upgradable_read`]. It coexists with plain readers but
+/// excludes writers and other upgradable readers, so it can later be turned into a
+/// [`SyncWriteGuard`] with [`upgrade`](Self::upgrade) without the data changing in
+/// between. Releases the lock when dropped.
+pub struct SyncUpgradableReadGuard<'a, T: ?Sized> {
+    data: NonNull<T>,
+    release: UpgradableRelease<'a>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: ?Sized> SyncUpgradableReadGuard<'a, T> {
+    /// Wraps an upgradable lock that the caller has already acquired.
+    ///
+    /// # Safety
+    /// The caller must hold the upgradable lock on `target` that protects `data`.
+    unsafe fn new(target: &'a dyn LockTarget, data: *mut T) -> Self {
+        SyncUpgradableReadGuard {
+            // SAFETY: `data` points into a live container allocation.
+            data: unsafe { NonNull::new_unchecked(data) },
+            release: UpgradableRelease { target },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Atomically upgrades the guard to a write guard.
+    ///
+    /// On native targets this blocks until the plain readers have left; new readers
+    /// are held back while it waits. On wasm32 the other readers can never leave
+    /// while the caller waits, so the upgrade fails with
+    /// `AccessError::BorrowConflict` and the guard is released. Use
+    /// [`try_upgrade`](Self::try_upgrade) to keep the guard on failure.
+    #[track_caller]
+    pub fn upgrade(this: Self) -> Result<SyncWriteGuard<'a, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let SyncUpgradableReadGuard { data, release, .. } = this;
+            let target = release.into_target();
+            target.backend().upgrade();
+            // SAFETY: the upgradable lock was just turned into the exclusive lock.
+            Ok(unsafe { SyncWriteGuard::new(target, data.as_ptr()) })
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            Self::try_upgrade(this).map_err(|_| AccessError::BorrowConflict)
+        }
+    }
+
+    /// Upgrades the guard to a write guard if no plain readers are active.
+    ///
+    /// Returns the original guard otherwise.
+    #[track_caller]
+    pub fn try_upgrade(this: Self) -> Result<SyncWriteGuard<'a, T>, Self> {
+        if !this.release.target.backend().try_upgrade() {
+            return Err(this);
+        }
+        let SyncUpgradableReadGuard { data, release, .. } = this;
+        let target = release.into_target();
+        // SAFETY: the upgradable lock was just turned into the exclusive lock.
+        Ok(unsafe { SyncWriteGuard::new(target, data.as_ptr()) })
+    }
+
+    /// Turns the guard into a plain read guard, letting another upgradable reader in.
+    pub fn downgrade(this: Self) -> SyncReadGuard<'a, T> {
+        let SyncUpgradableReadGuard { data, release, .. } = this;
+        let target = release.into_target();
+        target.backend().downgrade_upgradable();
+        // SAFETY: the upgradable lock was just turned into a shared lock.
+        unsafe { SyncReadGuard::new(target, data.as_ptr()) }
+    }
+}
+
+impl<T: ?Sized> Deref for SyncUpgradableReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the upgradable lock is held for the lifetime of the guard.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for SyncUpgradableReadGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+// SAFETY: the guard only hands out `&T`.
+unsafe impl<T: ?Sized + Sync> Sync for SyncUpgradableReadGuard<'_, T> {}
+
+/// Read guard that keeps its [`Shared`] container alive.
+///
+/// Created by [`Shared::read_owned`]. Unlike [`SyncReadGuard`] it does not borrow
+/// the container, so it can be returned from functions, stored in structs or moved
+/// into callbacks. `U` is the type the guard points to after
+/// [`map`](Self::map)ping; it defaults to the container's value type.
+pub struct OwnedSyncReadGuard<T, U: ?Sized = T, B: LockBackend = DefaultBackend> {
+    data: NonNull<U>,
+    release: OwnedReadRelease<T, B>,
+}
+
+impl<T, B: LockBackend> OwnedSyncReadGuard<T, T, B> {
+    /// Takes over a read guard obtained from `shared`.
+    fn from_guard(shared: Shared<T, B>, guard: SyncReadGuard<'_, T>) -> Self {
+        let SyncReadGuard { data, release, .. } = guard;
+        release.into_target();
+        OwnedSyncReadGuard {
+            data,
+            release: OwnedReadRelease { shared },
+        }
+    }
+}
+
+impl<T, U: ?Sized, B: LockBackend> OwnedSyncReadGuard<T, U, B> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// See [`SyncReadGuard::map`].
+    pub fn map<V: ?Sized, F>(this: Self, f: F) -> OwnedSyncReadGuard<T, V, B>
+    where
+        F: FnOnce(&U) -> &V,
+    {
+        let data = NonNull::from(f(&*this));
+        let OwnedSyncReadGuard { release, .. } = this;
+        OwnedSyncReadGuard { data, release }
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<V: ?Sized, F>(this: Self, f: F) -> Result<OwnedSyncReadGuard<T, V, B>, Self>
+    where
+        F: FnOnce(&U) -> Option<&V>,
+    {
+        match f(&*this).map(NonNull::from) {
+            Some(data) => {
+                let OwnedSyncReadGuard { release, .. } = this;
+                Ok(OwnedSyncReadGuard { data, release })
+            }
+            None => Err(this),
+        }
+    }
+
+    /// Splits the guard into two guards for different components of the locked data.
+    ///
+    /// The read lock stays held until both returned guards are dropped.
+    pub fn map_split<V: ?Sized, W: ?Sized, F>(
+        this: Self,
+        f: F,
+    ) -> (OwnedSyncReadGuard<T, V, B>, OwnedSyncReadGuard<T, W, B>)
+    where
+        F: FnOnce(&U) -> (&V, &W),
+    {
+        let (first, second) = f(&*this);
+        let (first, second) = (NonNull::from(first), NonNull::from(second));
+        let OwnedSyncReadGuard { release, .. } = this;
+        (
+            OwnedSyncReadGuard {
+                data: first,
+                release: release.split(),
+            },
+            OwnedSyncReadGuard {
+                data: second,
+                release,
+            },
+        )
+    }
+
+    /// Returns the container this guard keeps locked.
+    pub fn container(this: &Self) -> &Shared<T, B> {
+        &this.release.shared
+    }
+}
+
+impl<T, U: ?Sized, B: LockBackend> Deref for OwnedSyncReadGuard<T, U, B> {
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the shared lock is held and the container kept alive for the
+        // lifetime of the guard.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T, U: ?Sized + std::fmt::Debug, B: LockBackend> std::fmt::Debug
+    for OwnedSyncReadGuard<T, U, B>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+// SAFETY: the guard only hands out `&U`, and the lock may be released from any thread.
+unsafe impl<T, U: ?Sized + Sync, B: LockBackend> Send for OwnedSyncReadGuard<T, U, B> where
+    Shared<T, B>: Send
+{
+}
+
+// SAFETY: the guard only hands out `&U`.
+unsafe impl<T, U: ?Sized + Sync, B: LockBackend> Sync for OwnedSyncReadGuard<T, U, B> where
+    Shared<T, B>: Sync
+{
+}
+
+/// Write guard that keeps its [`Shared`] container alive.
+///
+/// Created by [`Shared::write_owned`]. Unlike [`SyncWriteGuard`] it does not borrow
+/// the container. On native targets, dropping the guard while the thread is
+/// panicking poisons the container.
+pub struct OwnedSyncWriteGuard<T, B: LockBackend = DefaultBackend> {
+    data: NonNull<T>,
+    release: OwnedWriteRelease<T, B>,
+}
+
+impl<T, B: LockBackend> OwnedSyncWriteGuard<T, B> {
+    /// Takes over a write guard obtained from `shared`.
+    fn from_guard(shared: Shared<T, B>, guard: SyncWriteGuard<'_, T>) -> Self {
+        let SyncWriteGuard { data, release, .. } = guard;
+        let origin = release.origin;
+        release.into_target();
+        OwnedSyncWriteGuard {
+            data,
+            release: OwnedWriteRelease { shared, origin },
+        }
+    }
+
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// See [`SyncWriteGuard::map`].
+    pub fn map<U: ?Sized, F>(mut this: Self, f: F) -> OwnedSyncMappedWriteGuard<T, U, B>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let data = NonNull::from(f(&mut *this));
+        let OwnedSyncWriteGuard { release, .. } = this;
+        OwnedSyncMappedWriteGuard { data, release }
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<U: ?Sized, F>(
+        mut this: Self,
+        f: F,
+    ) -> Result<OwnedSyncMappedWriteGuard<T, U, B>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match f(&mut *this).map(NonNull::from) {
+            Some(data) => {
+                let OwnedSyncWriteGuard { release, .. } = this;
+                Ok(OwnedSyncMappedWriteGuard { data, release })
+            }
+            None => Err(this),
+        }
+    }
+
+    /// Splits the guard into two guards for disjoint components of the locked data.
+    ///
+    /// The write lock stays held until both returned guards are dropped.
+    pub fn map_split<U: ?Sized, V: ?Sized, F>(
+        mut this: Self,
+        f: F,
+    ) -> (
+        OwnedSyncMappedWriteGuard<T, U, B>,
+        OwnedSyncMappedWriteGuard<T, V, B>,
+    )
+    where
+        F: FnOnce(&mut T) -> (&mut U, &mut V),
+    {
+        let (first, second) = f(&mut *this);
+        let (first, second) = (NonNull::from(first), NonNull::from(second));
+        let OwnedSyncWriteGuard { release, .. } = this;
+        (
+            OwnedSyncMappedWriteGuard {
+                data: first,
+                release: release.split(),
+            },
+            OwnedSyncMappedWriteGuard {
+                data: second,
+                release,
+            },
+        )
+    }
+
+    /// Atomically turns the write guard into a read guard.
+    ///
+    /// See [`SyncWriteGuard::downgrade`].
+    pub fn downgrade(this: Self) -> OwnedSyncReadGuard<T, T, B> {
+        let OwnedSyncWriteGuard { data, release } = this;
+        let shared = release.into_shared();
+        shared.inner.downgrade();
+        let guard = OwnedSyncReadGuard {
+            data,
+            release: OwnedReadRelease { shared },
+        };
+        guard.release.shared.inner.written();
+        guard
+    }
+
+    /// Returns the container this guard keeps locked.
+    pub fn container(this: &Self) -> &Shared<T, B> {
+        &this.release.shared
+    }
+}
+
+impl<T, B: LockBackend> Deref for OwnedSyncWriteGuard<T, B> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the exclusive lock is held and the container kept alive for the
+        // lifetime of the guard.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T, B: LockBackend> DerefMut for OwnedSyncWriteGuard<T, B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: the exclusive lock is held and the container kept alive for the
+        // lifetime of the guard.
+        unsafe { self.data.as_mut() }
+    }
+}
+
+impl<T: std::fmt::Debug, B: LockBackend> std::fmt::Debug for OwnedSyncWriteGuard<T, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+// SAFETY: the guard hands out `&mut T`, and the lock may be released from any thread.
+unsafe impl<T: Send, B: LockBackend> Send for OwnedSyncWriteGuard<T, B> where Shared<T, B>: Send {}
+
+// SAFETY: sharing the guard only hands out `&T`.
+unsafe impl<T: Sync, B: LockBackend> Sync for OwnedSyncWriteGuard<T, B> where Shared<T, B>: Sync {}
+
+/// Write guard for a component of the data locked by an [`OwnedSyncWriteGuard`].
+///
+/// Created by [`OwnedSyncWriteGuard::map`], [`OwnedSyncWriteGuard::try_map`] and
+/// [`OwnedSyncWriteGuard::map_split`]. The write lock is released once every guard
+/// produced from the original one has been dropped.
+pub struct OwnedSyncMappedWriteGuard<T, U: ?Sized, B: LockBackend = DefaultBackend> {
+    data: NonNull<U>,
+    release: OwnedWriteRelease<T, B>,
+}
+
+impl<T, U: ?Sized, B: LockBackend> OwnedSyncMappedWriteGuard<T, U, B> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// See [`SyncWriteGuard::map`].
+    pub fn map<V: ?Sized, F>(mut this: Self, f: F) -> OwnedSyncMappedWriteGuard<T, V, B>
+    where
+        F: FnOnce(&mut U) -> &mut V,
+    {
+        let data = NonNull::from(f(&mut *this));
+        let OwnedSyncMappedWriteGuard { release, .. } = this;
+        OwnedSyncMappedWriteGuard { data, release }
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<V: ?Sized, F>(
+        mut this: Self,
+        f: F,
+    ) -> Result<OwnedSyncMappedWriteGuard<T, V, B>, Self>
+    where
+        F: FnOnce(&mut U) -> Option<&mut V>,
+    {
+        match f(&mut *this).map(NonNull::from) {
+            Some(data) => {
+                let OwnedSyncMappedWriteGuard { release, .. } = this;
+                Ok(OwnedSyncMappedWriteGuard { data, release })
+            }
+            None => Err(this),
+        }
+    }
+
+    /// Splits the guard into two guards for disjoint components of the locked data.
+    ///
+    /// See [`SyncWriteGuard::map_split`].
+    pub fn map_split<V: ?Sized, W: ?Sized, F>(
+        mut this: Self,
+        f: F,
+    ) -> (
+        OwnedSyncMappedWriteGuard<T, V, B>,
+        OwnedSyncMappedWriteGuard<T, W, B>,
+    )
+    where
+        F: FnOnce(&mut U) -> (&mut V, &mut W),
+    {
+        let (first, second) = f(&mut *this);
+        let (first, second) = (NonNull::from(first), NonNull::from(second));
+        let OwnedSyncMappedWriteGuard { release, .. } = this;
+        (
+            OwnedSyncMappedWriteGuard {
+                data: first,
+                release: release.split(),
+            },
+            OwnedSyncMappedWriteGuard {
+                data: second,
+                release,
+            },
+        )
+    }
+
+    /// Returns the container this guard keeps locked.
+    pub fn container(this: &Self) -> &Shared<T, B> {
+        &this.release.shared
+    }
+}
+
+impl<T, U: ?Sized, B: LockBackend> Deref for OwnedSyncMappedWriteGuard<T, U, B> {
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the exclusive lock is held and the container kept alive for the
+        // lifetime of the guard.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T, U: ?Sized, B: LockBackend> DerefMut for OwnedSyncMappedWriteGuard<T, U, B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: the exclusive lock is held and the container kept alive for the
+        // lifetime of the guard.
+        unsafe { self.data.as_mut() }
+    }
+}
+
+impl<T, U: ?Sized + std::fmt::Debug, B: LockBackend> std::fmt::Debug
+    for OwnedSyncMappedWriteGuard<T, U, B>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+// SAFETY: the guard hands out `&mut U`, and the lock may be released from any thread.
+unsafe impl<T, U: ?Sized + Send, B: LockBackend> Send for OwnedSyncMappedWriteGuard<T, U, B> where
+    Shared<T, B>: Send
+{
+}
+
+// SAFETY: sharing the guard only hands out `&U`.
+unsafe impl<T, U: ?Sized + Sync, B: LockBackend> Sync for OwnedSyncMappedWriteGuard<T, U, B> where
+    Shared<T, B>: Sync
+{
+}
+
+/// Wakes [`AsyncShared::changed`] waiters and snapshot streams when dropped.
+///
+/// Async write guards carry one as their last field, so the notification goes out
+/// after the write lock has been released (or downgraded).
+#[cfg(feature = "async")]
+#[derive(Debug)]
+struct WriteNotice<S: Borrow<tokio::sync::watch::Sender<()>>>(S);
+
+#[cfg(feature = "async")]
+impl<S: Borrow<tokio::sync::watch::Sender<()>>> Drop for WriteNotice<S> {
+    fn drop(&mut self) {
+        self.0.borrow().send_replace(());
+    }
+}
+
+/// Refuses to block a thread that has entered a tokio runtime, where tokio's own
+/// blocking methods would panic.
+///
+/// Only tokio runtimes can be detected, and only with the `tokio-rt` feature. Without
+/// it every thread may block.
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+fn check_blocking_allowed() -> Result<(), AccessError> {
+    #[cfg(feature = "tokio-rt")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(AccessError::BlockingInRuntime);
+    }
+    Ok(())
+}
+
+/// Read guard for asynchronous access.
+///
+/// Use [`AsyncReadGuard::map`] to narrow the guard to a part of the locked data.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncReadGuard<'a, T: ?Sized>(AsyncReadLock<'a, T>);
+
+/// The lock behind an [`AsyncReadGuard`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+enum AsyncReadLock<'a, T: ?Sized> {
+    Tokio(tokio::sync::RwLockReadGuard<'a, T>),
+    /// Taken from a [`Shared`] container, or holding an [`AsyncSharedMutex`] for a
+    /// [`SharedAny`].
+    Shared(SyncReadGuard<'a, T>),
+}
+
+// SAFETY: tokio guards are `Send` for `T: Sync`, and `Shared` backends on native
+// targets may be released from any thread, while wasm32 has only one thread. An
+// `AsyncSharedMutex` guard behind the `Shared` variant is only taken for `T: Send`
+// and never split, so its `Rc` has no other owner.
+#[cfg(feature = "async")]
+unsafe impl<T: ?Sized + Sync> Send for AsyncReadGuard<'_, T> {}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> AsyncReadGuard<'a, T> {
+    fn from_tokio(guard: tokio::sync::RwLockReadGuard<'a, T>) -> Self {
+        AsyncReadGuard(AsyncReadLock::Tokio(guard))
+    }
+
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// This is an associated function so it does not shadow methods of `T`; call it
+    /// as `AsyncReadGuard::map(guard, |v| &v.field)`.
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> AsyncReadGuard<'a, U>
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        AsyncReadGuard(match this.0 {
+            AsyncReadLock::Tokio(guard) => {
+                AsyncReadLock::Tokio(tokio::sync::RwLockReadGuard::map(guard, f))
+            }
+            AsyncReadLock::Shared(guard) => AsyncReadLock::Shared(SyncReadGuard::map(guard, f)),
+        })
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<U: ?Sized, F>(this: Self, f: F) -> Result<AsyncReadGuard<'a, U>, Self>
+    where
+        F: FnOnce(&T) -> Option<&U>,
+    {
+        match this.0 {
+            AsyncReadLock::Tokio(guard) => tokio::sync::RwLockReadGuard::try_map(guard, f)
+                .map(AsyncReadGuard::from_tokio)
+                .map_err(AsyncReadGuard::from_tokio),
+            AsyncReadLock::Shared(guard) => SyncReadGuard::try_map(guard, f)
+                .map(|guard| AsyncReadGuard(AsyncReadLock::Shared(guard)))
+                .map_err(|guard| AsyncReadGuard(AsyncReadLock::Shared(guard))),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> Deref for AsyncReadGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            AsyncReadLock::Tokio(guard) => guard,
+            AsyncReadLock::Shared(guard) => guard,
+        }
+    }
+}
+
+/// Write guard for asynchronous access.
+///
+/// Use [`AsyncWriteGuard::map`] to narrow the guard to a part of the locked data.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncWriteGuard<'a, T: ?Sized>(AsyncWriteLock<'a, T>);
+
+/// The lock behind an [`AsyncWriteGuard`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+enum AsyncWriteLock<'a, T: ?Sized> {
+    Tokio(
+        tokio::sync::RwLockWriteGuard<'a, T>,
+        WriteNotice<&'a tokio::sync::watch::Sender<()>>,
+    ),
+    /// Taken from a [`Shared`] container, or holding an [`AsyncSharedMutex`] for a
+    /// [`SharedAny`].
+    Shared(SyncWriteGuard<'a, T>),
+}
+
+// SAFETY: tokio guards are `Send` for `T: Send + Sync`, and `Shared` backends on
+// native targets may be released from any thread, while wasm32 has only one thread.
+// An `AsyncSharedMutex` guard behind the `Shared` variant is never split, so its `Rc`
+// has no other owner.
+#[cfg(feature = "async")]
+unsafe impl<T: ?Sized + Send + Sync> Send for AsyncWriteGuard<'_, T> {}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> AsyncWriteGuard<'a, T> {
+    fn from_tokio(
+        guard: tokio::sync::RwLockWriteGuard<'a, T>,
+        notice: WriteNotice<&'a tokio::sync::watch::Sender<()>>,
+    ) -> Self {
+        AsyncWriteGuard(AsyncWriteLock::Tokio(guard, notice))
+    }
+
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// This is an associated function so it does not shadow methods of `T`; call it
+    /// as `AsyncWriteGuard::map(guard, |v| &mut v.field)`.
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> AsyncMappedWriteGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        AsyncMappedWriteGuard(match this.0 {
+            AsyncWriteLock::Tokio(guard, notice) => {
+                AsyncMappedWriteLock::Tokio(tokio::sync::RwLockWriteGuard::map(guard, f), notice)
+            }
+            AsyncWriteLock::Shared(guard) => {
+                AsyncMappedWriteLock::Shared(SyncWriteGuard::map(guard, f))
+            }
+        })
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<U: ?Sized, F>(this: Self, f: F) -> Result<AsyncMappedWriteGuard<'a, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match this.0 {
+            AsyncWriteLock::Tokio(guard, notice) => {
+                match tokio::sync::RwLockWriteGuard::try_map(guard, f) {
+                    Ok(guard) => Ok(AsyncMappedWriteGuard(AsyncMappedWriteLock::Tokio(
+                        guard, notice,
+                    ))),
+                    Err(guard) => Err(AsyncWriteGuard::from_tokio(guard, notice)),
+                }
+            }
+            AsyncWriteLock::Shared(guard) => match SyncWriteGuard::try_map(guard, f) {
+                Ok(guard) => Ok(AsyncMappedWriteGuard(AsyncMappedWriteLock::Shared(guard))),
+                Err(guard) => Err(AsyncWriteGuard(AsyncWriteLock::Shared(guard))),
+            },
+        }
+    }
+
+    /// Atomically turns the write guard into a read guard.
+    ///
+    /// Other readers may proceed once this returns, but no writer can acquire the
+    /// lock in between.
+    pub fn downgrade(this: Self) -> AsyncReadGuard<'a, T> {
+        match this.0 {
+            AsyncWriteLock::Tokio(guard, _notice) => AsyncReadGuard::from_tokio(guard.downgrade()),
+            AsyncWriteLock::Shared(guard) => {
+                AsyncReadGuard(AsyncReadLock::Shared(SyncWriteGuard::downgrade(guard)))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> Deref for AsyncWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            AsyncWriteLock::Tokio(guard, _) => guard,
+            AsyncWriteLock::Shared(guard) => guard,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> DerefMut for AsyncWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.0 {
+            AsyncWriteLock::Tokio(guard, _) => guard,
+            AsyncWriteLock::Shared(guard) => guard,
+        }
+    }
+}
+
+/// An async guard kept by the sync guards made from it.
+#[cfg(feature = "async")]
+enum HeldAsyncGuard<'a, T: ?Sized> {
+    Read(AsyncReadGuard<'a, T>),
+    Write(AsyncWriteGuard<'a, T>),
+}
+
+#[cfg(feature = "async")]
+impl<T: ?Sized> GuardLock for std::cell::RefCell<Option<HeldAsyncGuard<'_, T>>> {
+    fn downgrade(&self) {
+        let mut held = self.borrow_mut();
+        *held = match held.take() {
+            Some(HeldAsyncGuard::Write(guard)) => {
+                Some(HeldAsyncGuard::Read(AsyncWriteGuard::downgrade(guard)))
+            }
+            other => other,
+        };
+    }
+}
+
+// A mutex has no shared mode, so a downgraded guard stays exclusive.
+#[cfg(feature = "async")]
+impl<T: ?Sized> GuardLock for tokio::sync::MutexGuard<'_, T> {
+    fn downgrade(&self) {}
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> AsyncReadGuard<'a, T> {
+    /// Turns the guard into a sync guard holding the same lock.
+    fn into_sync(this: Self) -> SyncReadGuard<'a, T> {
+        match this.0 {
+            AsyncReadLock::Shared(guard) => guard,
+            AsyncReadLock::Tokio(guard) => {
+                let data = NonNull::from(&*guard);
+                let held = HeldAsyncGuard::Read(AsyncReadGuard::from_tokio(guard));
+                SyncReadGuard {
+                    data,
+                    release: ReadRelease {
+                        lock: HeldLock::Guard(std::rc::Rc::new(std::cell::RefCell::new(Some(
+                            held,
+                        )))),
+                    },
+                    _marker: PhantomData,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> AsyncWriteGuard<'a, T> {
+    /// Turns the guard into a sync guard holding the same lock.
+    fn into_sync(this: Self) -> SyncWriteGuard<'a, T> {
+        match this.0 {
+            AsyncWriteLock::Shared(guard) => guard,
+            AsyncWriteLock::Tokio(guard, notice) => {
+                let mut guard = AsyncWriteGuard::from_tokio(guard, notice);
+                let data = NonNull::from(&mut *guard);
+                let held = HeldAsyncGuard::Write(guard);
+                SyncWriteGuard {
+                    data,
+                    release: WriteRelease {
+                        lock: HeldLock::Guard(std::rc::Rc::new(std::cell::RefCell::new(Some(
+                            held,
+                        )))),
+                        origin: WriteOrigin::current(),
+                    },
+                    _marker: PhantomData,
+                }
+            }
+        }
+    }
+}
+
+/// Write guard for a component of the data locked by an [`AsyncWriteGuard`].
+///
+/// Created by [`AsyncWriteGuard::map`] and [`AsyncWriteGuard::try_map`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncMappedWriteGuard<'a, T: ?Sized>(AsyncMappedWriteLock<'a, T>);
+
+/// The lock behind an [`AsyncMappedWriteGuard`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+enum AsyncMappedWriteLock<'a, T: ?Sized> {
+    Tokio(
+        tokio::sync::RwLockMappedWriteGuard<'a, T>,
+        WriteNotice<&'a tokio::sync::watch::Sender<()>>,
+    ),
+    /// Mapped from the `Shared` variant of [`AsyncWriteLock`].
+    Shared(SyncMappedWriteGuard<'a, T>),
+}
+
+// SAFETY: as for `AsyncWriteGuard`.
+#[cfg(feature = "async")]
+unsafe impl<T: ?Sized + Send + Sync> Send for AsyncMappedWriteGuard<'_, T> {}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> AsyncMappedWriteGuard<'a, T> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// See [`AsyncWriteGuard::map`].
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> AsyncMappedWriteGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        AsyncMappedWriteGuard(match this.0 {
+            AsyncMappedWriteLock::Tokio(guard, notice) => AsyncMappedWriteLock::Tokio(
+                tokio::sync::RwLockMappedWriteGuard::map(guard, f),
+                notice,
+            ),
+            AsyncMappedWriteLock::Shared(guard) => {
+                AsyncMappedWriteLock::Shared(SyncMappedWriteGuard::map(guard, f))
+            }
+        })
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<U: ?Sized, F>(this: Self, f: F) -> Result<AsyncMappedWriteGuard<'a, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match this.0 {
+            AsyncMappedWriteLock::Tokio(guard, notice) => {
+                match tokio::sync::RwLockMappedWriteGuard::try_map(guard, f) {
+                    Ok(guard) => Ok(AsyncMappedWriteGuard(AsyncMappedWriteLock::Tokio(
+                        guard, notice,
+                    ))),
+                    Err(guard) => Err(AsyncMappedWriteGuard(AsyncMappedWriteLock::Tokio(
+                        guard, notice,
+                    ))),
+                }
+            }
+            AsyncMappedWriteLock::Shared(guard) => SyncMappedWriteGuard::try_map(guard, f)
+                .map(|guard| AsyncMappedWriteGuard(AsyncMappedWriteLock::Shared(guard)))
+                .map_err(|guard| AsyncMappedWriteGuard(AsyncMappedWriteLock::Shared(guard))),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> Deref for AsyncMappedWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match &self.0 {
+            AsyncMappedWriteLock::Tokio(guard, _) => guard,
+            AsyncMappedWriteLock::Shared(guard) => guard,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> DerefMut for AsyncMappedWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.0 {
+            AsyncMappedWriteLock::Tokio(guard, _) => guard,
+            AsyncMappedWriteLock::Shared(guard) => guard,
+        }
+    }
+}
+
+/// Read guard that keeps its [`AsyncShared`] container alive.
+///
+/// Created by [`AsyncShared::read_owned_async`]. It does not borrow the container,
+/// so it can be moved into spawned tasks. `U` is the type the guard points to after
+/// [`map`](Self::map)ping; it defaults to the container's value type.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct OwnedAsyncReadGuard<T: ?Sized, U: ?Sized = T>(tokio::sync::OwnedRwLockReadGuard<T, U>);
+
+#[cfg(feature = "async")]
+impl<T: ?Sized, U: ?Sized> OwnedAsyncReadGuard<T, U> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// See [`AsyncReadGuard::map`].
+    pub fn map<V: ?Sized, F>(this: Self, f: F) -> OwnedAsyncReadGuard<T, V>
+    where
+        F: FnOnce(&U) -> &V,
+    {
+        OwnedAsyncReadGuard(tokio::sync::OwnedRwLockReadGuard::map(this.0, f))
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<V: ?Sized, F>(this: Self, f: F) -> Result<OwnedAsyncReadGuard<T, V>, Self>
+    where
+        F: FnOnce(&U) -> Option<&V>,
+    {
+        tokio::sync::OwnedRwLockReadGuard::try_map(this.0, f)
+            .map(OwnedAsyncReadGuard)
+            .map_err(OwnedAsyncReadGuard)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: ?Sized, U: ?Sized> Deref for OwnedAsyncReadGuard<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.deref()
+    }
+}
+
+/// Write guard that keeps its [`AsyncShared`] container alive.
+///
+/// Created by [`AsyncShared::write_owned_async`]. It does not borrow the container,
+/// so it can be moved into spawned tasks.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct OwnedAsyncWriteGuard<T: ?Sized>(
+    tokio::sync::OwnedRwLockWriteGuard<T>,
+    WriteNotice<Arc<tokio::sync::watch::Sender<()>>>,
+);
+
+#[cfg(feature = "async")]
+impl<T: ?Sized> OwnedAsyncWriteGuard<T> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// See [`AsyncWriteGuard::map`].
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> OwnedAsyncMappedWriteGuard<T, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        OwnedAsyncMappedWriteGuard(tokio::sync::OwnedRwLockWriteGuard::map(this.0, f), this.1)
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<U: ?Sized, F>(this: Self, f: F) -> Result<OwnedAsyncMappedWriteGuard<T, U>, Self>
+    where
+        F: FnOnce(&mut T) -> Option<&mut U>,
+    {
+        match tokio::sync::OwnedRwLockWriteGuard::try_map(this.0, f) {
+            Ok(guard) => Ok(OwnedAsyncMappedWriteGuard(guard, this.1)),
+            Err(guard) => Err(OwnedAsyncWriteGuard(guard, this.1)),
+        }
+    }
+
+    /// Atomically turns the write guard into a read guard.
+    ///
+    /// See [`AsyncWriteGuard::downgrade`].
+    pub fn downgrade(this: Self) -> OwnedAsyncReadGuard<T> {
+        let OwnedAsyncWriteGuard(guard, _notice) = this;
+        OwnedAsyncReadGuard(guard.downgrade())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: ?Sized> Deref for OwnedAsyncWriteGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.deref()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: ?Sized> DerefMut for OwnedAsyncWriteGuard<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.deref_mut()
+    }
+}
+
+/// Write guard for a component of the data locked by an [`OwnedAsyncWriteGuard`].
+///
+/// Created by [`OwnedAsyncWriteGuard::map`] and [`OwnedAsyncWriteGuard::try_map`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct OwnedAsyncMappedWriteGuard<T: ?Sized, U: ?Sized>(
+    tokio::sync::OwnedRwLockMappedWriteGuard<T, U>,
+    WriteNotice<Arc<tokio::sync::watch::Sender<()>>>,
+);
+
+#[cfg(feature = "async")]
+impl<T: ?Sized, U: ?Sized> OwnedAsyncMappedWriteGuard<T, U> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// See [`AsyncWriteGuard::map`].
+    pub fn map<V: ?Sized, F>(this: Self, f: F) -> OwnedAsyncMappedWriteGuard<T, V>
+    where
+        F: FnOnce(&mut U) -> &mut V,
+    {
+        OwnedAsyncMappedWriteGuard(
+            tokio::sync::OwnedRwLockMappedWriteGuard::map(this.0, f),
+            this.1,
+        )
+    }
+
+    /// Makes a guard for an optional component of the locked data.
+    ///
+    /// Returns the original guard if `f` returns `None`.
+    pub fn try_map<V: ?Sized, F>(this: Self, f: F) -> Result<OwnedAsyncMappedWriteGuard<T, V>, Self>
+    where
+        F: FnOnce(&mut U) -> Option<&mut V>,
+    {
+        match tokio::sync::OwnedRwLockMappedWriteGuard::try_map(this.0, f) {
+            Ok(guard) => Ok(OwnedAsyncMappedWriteGuard(guard, this.1)),
+            Err(guard) => Err(OwnedAsyncMappedWriteGuard(guard, this.1)),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: ?Sized, U: ?Sized> Deref for OwnedAsyncMappedWriteGuard<T, U> {
+    type Target = U;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.deref()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: ?Sized, U: ?Sized> DerefMut for OwnedAsyncMappedWriteGuard<T, U> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.deref_mut()
+    }
+}
+
+/// Guard for an [`AsyncSharedMutex`].
+///
+/// Releases the lock when dropped.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncMutexGuard<'a, T: ?Sized>(tokio::sync::MutexGuard<'a, T>);
+
+#[cfg(feature = "async")]
+impl<'a, T: ?Sized> AsyncMutexGuard<'a, T> {
+    /// Turns the guard into a sync guard holding the same lock.
+    fn into_sync(this: Self) -> SyncWriteGuard<'a, T> {
+        let mut guard = this.0;
+        let data = NonNull::from(&mut *guard);
+        SyncWriteGuard {
+            data,
+            release: WriteRelease {
+                lock: HeldLock::Guard(std::rc::Rc::new(guard)),
+                origin: WriteOrigin::current(),
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: ?Sized> Deref for AsyncMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.deref()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: ?Sized> DerefMut for AsyncMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.deref_mut()
+    }
+}
+
+/// Read guard for a [`LocalAsyncShared`].
+///
+/// Use [`LocalAsyncReadGuard::map`] to narrow the guard to a part of the locked data.
+pub struct LocalAsyncReadGuard<'a, T: ?Sized> {
+    lock: &'a lock::LocalAsyncLock,
+    data: NonNull<T>,
+    _marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: ?Sized> LocalAsyncReadGuard<'a, T> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// This is an associated function so it does not shadow methods of `T`; call it
+    /// as `LocalAsyncReadGuard::map(guard, |v| &v.field)`.
+    pub fn map<U: ?Sized, F>(this: Self, f: F) -> LocalAsyncReadGuard<'a, U>
+    where
+        F: FnOnce(&T) -> &U,
+    {
+        let data = NonNull::from(f(&*this));
+        let lock = this.lock;
+        std::mem::forget(this);
+        LocalAsyncReadGuard {
+            lock,
+            data,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for LocalAsyncReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the guard holds the lock in shared mode.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T: ?Sized> Drop for LocalAsyncReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.unlock_shared();
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for LocalAsyncReadGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Write guard for a [`LocalAsyncShared`].
+///
+/// Use [`LocalAsyncWriteGuard::map`] to narrow the guard to a part of the locked data.
+pub struct LocalAsyncWriteGuard<'a, T: ?Sized> {
+    lock: &'a lock::LocalAsyncLock,
+    data: NonNull<T>,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: ?Sized> LocalAsyncWriteGuard<'a, T> {
+    /// Makes a guard for a component of the locked data.
+    ///
+    /// This is an associated function so it does not shadow methods of `T`; call it
+    /// as `LocalAsyncWriteGuard::map(guard, |v| &mut v.field)`.
+    pub fn map<U: ?Sized, F>(mut this: Self, f: F) -> LocalAsyncWriteGuard<'a, U>
+    where
+        F: FnOnce(&mut T) -> &mut U,
+    {
+        let data = NonNull::from(f(&mut *this));
+        let lock = this.lock;
+        std::mem::forget(this);
+        LocalAsyncWriteGuard {
+            lock,
+            data,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Turns the write guard into a read guard without letting a writer in between.
+    ///
+    /// Tasks waiting to read are woken.
+    pub fn downgrade(this: Self) -> LocalAsyncReadGuard<'a, T> {
+        let lock = this.lock;
+        let data = this.data;
+        std::mem::forget(this);
+        lock.downgrade();
+        LocalAsyncReadGuard {
+            lock,
+            data,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for LocalAsyncWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: the guard holds the lock in exclusive mode.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for LocalAsyncWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: the guard holds the lock in exclusive mode.
+        unsafe { self.data.as_mut() }
+    }
+}
+
+impl<T: ?Sized> Drop for LocalAsyncWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.unlock_exclusive();
+    }
+}
+
+impl<T: ?Sized + std::fmt::Debug> std::fmt::Debug for LocalAsyncWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// A synchronous shared container that works across platforms.
+///
+/// On wasm32 targets: uses `Rc` with a `RefCell`-style borrow flag
+/// On other targets: uses `Arc` with a condvar-based reader-writer lock
+///
+/// The lock can be swapped for another [`LockBackend`] through `B`; see
+/// [`with_backend`](Self::with_backend).
+///
+/// `PartialEq`, `PartialOrd` and `Display` read the value, blocking like
+/// [`read`](SyncAccess::read). If it can't be read, for example because the lock is
+/// poisoned, the value is unequal to everything and `Display` prints the error in
+/// angle brackets. [`try_eq`](Self::try_eq) and [`try_cmp`](Self::try_cmp) report
+/// the error instead.
+pub struct Shared<T, B = DefaultBackend> {
+    #[cfg(target_arch = "wasm32")]
+    inner: std::rc::Rc<SharedInner<T, B>>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    inner: std::sync::Arc<SharedInner<T, B>>,
+}
+
+/// A weak reference to a `Shared<T, B>`.
+#[derive(Debug)]
+pub struct WeakShared<T, B = DefaultBackend> {
+    #[cfg(target_arch = "wasm32")]
+    inner: std::rc::Weak<SharedInner<T, B>>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    inner: std::sync::Weak<SharedInner<T, B>>,
+}
+
+/// Keeps an observer registered with a [`Shared`] container.
+///
+/// Returned by [`Shared::subscribe`] and [`Shared::subscribe_changes`]. Dropping the
+/// subscription unsubscribes the observer.
+#[must_use = "dropping a `Subscription` unsubscribes the observer"]
+pub struct Subscription {
+    registry: RegistryRef,
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(registry) = self.registry.upgrade() {
+            registry.unsubscribe(self.id);
+        }
+    }
+}
+
+impl std::fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `Send + Sync` on native targets, and no requirement on wasm32.
+///
+/// Bounds callbacks registered with a [`Shared`] container: on native targets they
+/// run on whichever thread wrote to the container, while on wasm32 they may capture
+/// `Rc` and other single-threaded state.
+#[cfg(not(target_arch = "wasm32"))]
+pub trait MaybeSendSync: Send + Sync {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: Send + Sync + ?Sized> MaybeSendSync for T {}
+
+/// `Send + Sync` on native targets, and no requirement on wasm32.
+///
+/// Bounds callbacks registered with a [`Shared`] container: on native targets they
+/// run on whichever thread wrote to the container, while on wasm32 they may capture
+/// `Rc` and other single-threaded state.
+#[cfg(target_arch = "wasm32")]
+pub trait MaybeSendSync {}
+
+#[cfg(target_arch = "wasm32")]
+impl<T: ?Sized> MaybeSendSync for T {}
+
+#[cfg(not(target_arch = "wasm32"))]
+type Observer<T> = std::sync::Arc<dyn Fn(&T) + Send + Sync>;
+
+#[cfg(target_arch = "wasm32")]
+type Observer<T> = std::rc::Rc<dyn Fn(&T)>;
+
+#[cfg(not(target_arch = "wasm32"))]
+type RegistryRef = std::sync::Weak<dyn ObserverRegistry + Send + Sync>;
+
+#[cfg(target_arch = "wasm32")]
+type RegistryRef = std::rc::Weak<dyn ObserverRegistry>;
+
+/// Lets a `Subscription` unregister its observer without knowing the value type.
+trait ObserverRegistry {
+    fn unsubscribe(&self, id: u64);
+}
+
+/// Observers registered with a `Shared<T>`.
+struct Observers<T> {
+    list: std::sync::Mutex<ObserverList<T>>,
+}
+
+struct ObserverList<T> {
+    next_id: u64,
+    entries: Vec<(u64, Observer<T>)>,
+}
+
+impl<T> Observers<T> {
+    fn new() -> Self {
+        Observers {
+            list: std::sync::Mutex::new(ObserverList {
+                next_id: 0,
+                entries: Vec::new(),
+            }),
+        }
+    }
+
+    fn list(&self) -> std::sync::MutexGuard<'_, ObserverList<T>> {
+        // Observers never run while the list is locked, so poisoning carries no
+        // information here.
+        self.list
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn add(&self, observer: Observer<T>) -> u64 {
+        let mut list = self.list();
+        let id = list.next_id;
+        list.next_id += 1;
+        list.entries.push((id, observer));
+        id
+    }
+
+    fn remove(&self, id: u64) {
+        self.list().entries.retain(|(entry, _)| *entry != id);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.list().entries.is_empty()
+    }
+
+    /// Copies the current observers so they can run without the list locked.
+    fn snapshot(&self) -> Vec<Observer<T>> {
+        let list = self.list();
+        list.entries
+            .iter()
+            .map(|(_, observer)| observer.clone())
+            .collect()
+    }
+}
+
+/// Lock and data shared by all handles of a `Shared<T, B>`.
+struct SharedInner<T, B> {
+    lock: lock::ContainerLock<B>,
+    /// Counts the guards sharing the backend lock after `map_split`.
+    holders: lock::Holders,
+    #[cfg(not(target_arch = "wasm32"))]
+    poison: lock::Poison,
+    #[cfg(not(target_arch = "wasm32"))]
+    signal: lock::WriteSignal,
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    policy: PoisonPolicy<T>,
+    observers: Observers<T>,
+    data: UnsafeCell<T>,
+}
+
+// SAFETY: access to `data` is synchronized by `lock`, as for `std::sync::RwLock`.
+#[cfg(not(target_arch = "wasm32"))]
+unsafe impl<T: Send, B: LockBackend> Send for SharedInner<T, B> {}
+
+#[cfg(not(target_arch = "wasm32"))]
+unsafe impl<T: Send + Sync, B: LockBackend> Sync for SharedInner<T, B> {}
+
+// Like `std::sync::RwLock`, a panic while writing poisons the container instead of
+// exposing a broken value.
+#[cfg(not(target_arch = "wasm32"))]
+impl<T, B> std::panic::UnwindSafe for SharedInner<T, B> {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T, B> std::panic::RefUnwindSafe for SharedInner<T, B> {}
+
+impl<T, B: LockBackend> SharedInner<T, B> {
+    fn new(value: T, policy: PoisonPolicy<T>) -> Self {
+        SharedInner {
+            lock: lock::ContainerLock::<B>::new(),
+            holders: lock::Holders::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            poison: lock::Poison::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            signal: lock::WriteSignal::default(),
+            policy,
+            observers: Observers::new(),
+            data: UnsafeCell::new(value),
+        }
+    }
+}
+
+impl<T, B> SharedInner<T, B> {
+    /// Returns whether the poison policy refuses access to the value.
+    fn refuses_access(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.poison.is_poisoned() && !matches!(self.policy, PoisonPolicy::Ignore)
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            false
+        }
+    }
+}
+
+impl<T, B: LockBackend> LockTarget for SharedInner<T, B> {
+    fn backend(&self) -> &dyn LockBackend {
+        &self.lock
+    }
+
+    fn holders(&self) -> &lock::Holders {
+        &self.holders
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn poison(&self, info: PoisonInfo) {
+        self.poison.poison(info);
+    }
+
+    fn release_exclusive(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let PoisonPolicy::Reset(factory) = &self.policy
+            && self.poison.is_poisoned()
+        {
+            // SAFETY: the caller is the last holder of the exclusive lock.
+            unsafe { *self.data.get() = factory() };
+            self.poison.clear();
+        }
+
+        if std::thread::panicking() || self.observers.is_empty() {
+            self.lock.unlock_exclusive();
+            #[cfg(not(target_arch = "wasm32"))]
+            self.signal.notify();
+            return;
+        }
+
+        // Let observers see exactly the value this write left behind.
+        self.downgrade();
+        // Releases the shared lock even if an observer panics.
+        let _release = ReadRelease {
+            lock: HeldLock::Target(self),
+        };
+        self.written();
+    }
+
+    fn downgrade(&self) {
+        self.lock.downgrade();
+        #[cfg(not(target_arch = "wasm32"))]
+        self.signal.notify();
+    }
+
+    fn written(&self) {
+        if std::thread::panicking() {
+            return;
+        }
+        let observers = self.observers.snapshot();
+        // SAFETY: the caller holds the shared lock.
+        let value = unsafe { &*self.data.get() };
+        for observer in &observers {
+            observer(value);
+        }
+    }
+}
+
+impl<T, B> ObserverRegistry for SharedInner<T, B> {
+    fn unsubscribe(&self, id: u64) {
+        self.observers.remove(id);
+    }
+}
+
+/// `Debug` output of a container: its value if that can be read without blocking,
+/// or else the lock state and the handle counts.
+///
+/// Every container reports the same states:
+///
+/// - `<busy>`: the lock can't be shared right now. It is held in a conflicting mode,
+///   which for a mutex means held at all, or a writer waits for it on a lock that
+///   prefers writers.
+/// - `<poisoned>`: a writer panicked and the container refuses access.
+/// - `<other thread>`: a `ThreadBoundShared` is printed off its owner thread.
+/// - `<dropped>`: a weak reference outlived its container.
+struct DebugContainer<G> {
+    name: &'static str,
+    value: Result<G, &'static str>,
+    strong: usize,
+    weak: usize,
+}
+
+impl<G> std::fmt::Debug for DebugContainer<G>
+where
+    G: Deref<Target: std::fmt::Debug>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct(self.name);
+        match &self.value {
+            Ok(value) => d.field("value", &&**value),
+            Err(state) => d
+                .field("value", &format_args!("<{state}>"))
+                .field("strong", &self.strong)
+                .field("weak", &self.weak),
+        };
+        d.finish()
+    }
+}
+
+/// A synchronous shared container that only ever grants exclusive access.
+///
+/// [`Shared`] lets readers share the value, so it is only `Send + Sync` for
+/// `T: Send + Sync`. Here every lock, including [`read`](SyncAccess::read), is
+/// exclusive, so `T: Send` is enough, as for `std::sync::Mutex`. This suits payloads
+/// such as `Cell`s or channel senders.
+///
+/// Closure access comes from [`SyncAccess`]. Poisoning works as for [`Shared`].
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use shared_container::SharedMutex;
+///
+/// let hits = SharedMutex::new(Cell::new(0));
+/// let other = hits.clone();
+/// std::thread::spawn(move || other.lock().unwrap().set(1))
+///     .join()
+///     .unwrap();
+/// assert_eq!(hits.lock().unwrap().get(), 1);
+/// ```
+pub struct SharedMutex<T> {
+    inner: Shared<T, lock::MutexBackend>,
+}
+
+/// A weak reference to a `SharedMutex<T>`.
+#[derive(Debug)]
+pub struct WeakSharedMutex<T> {
+    inner: WeakShared<T, lock::MutexBackend>,
+}
+
+// SAFETY: `MutexBackend` makes every lock mode exclusive, so the value is only ever
+// accessed by one thread at a time, as for `std::sync::Mutex`.
+#[cfg(not(target_arch = "wasm32"))]
+unsafe impl<T: Send> Send for SharedMutex<T> {}
+
+#[cfg(not(target_arch = "wasm32"))]
+unsafe impl<T: Send> Sync for SharedMutex<T> {}
+
+#[cfg(not(target_arch = "wasm32"))]
+unsafe impl<T: Send> Send for WeakSharedMutex<T> {}
+
+#[cfg(not(target_arch = "wasm32"))]
+unsafe impl<T: Send> Sync for WeakSharedMutex<T> {}
+
+/// A single-threaded synchronous shared container, on every target.
+///
+/// Built on `Rc` and a `RefCell`-style borrow flag, like [`Shared`] on wasm32, so
+/// single-threaded code and its tests behave the same on native targets. A
+/// conflicting borrow is reported as `AccessError::BorrowConflict` instead of
+/// blocking, and a panic while writing does not poison the container. The container
+/// is neither `Send` nor `Sync` and places no bounds on `T`.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use shared_container::{AccessError, LocalShared, SyncAccess};
+///
+/// let state = LocalShared::new(Rc::new(String::from("gui")));
+/// let reader = state.read().unwrap();
+/// assert_eq!(state.write().unwrap_err(), AccessError::BorrowConflict);
+/// drop(reader);
+/// assert!(state.write().is_ok());
+/// ```
+pub struct LocalShared<T> {
+    inner: std::rc::Rc<LocalInner<T>>,
+}
+
+/// A weak reference to a `LocalShared<T>`.
+#[derive(Debug)]
+pub struct WeakLocalShared<T> {
+    inner: std::rc::Weak<LocalInner<T>>,
+}
+
+/// A single-threaded shared container that can be moved between threads.
+///
+/// Like [`LocalShared`] it is a cheap borrow flag with no bounds on `T`, but the
+/// handles are reference counted with `Arc` and the container remembers the thread
+/// that created it. The handles are `Send` and `Sync`, so `!Send` state such as `Rc`s
+/// can live in types that must be `Send`. Every access checks the calling thread
+/// and fails with `AccessError::WrongThread` on any other thread; conflicting
+/// borrows are reported as `AccessError::BorrowConflict`.
+///
+/// The value is dropped with the last handle. If that happens on another thread,
+/// the value cannot be dropped safely and is leaked instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use shared_container::{AccessError, SyncAccess, ThreadBoundShared};
+///
+/// let state = ThreadBoundShared::new(Rc::new(5));
+/// assert_eq!(**state.read().unwrap(), 5);
+///
+/// let handle = state.clone();
+/// let result = std::thread::spawn(move || handle.get_cloned().map(|rc| *rc))
+///     .join()
+///     .unwrap();
+/// assert_eq!(result, Err(AccessError::WrongThread));
+/// ```
+pub struct ThreadBoundShared<T> {
+    inner: std::sync::Arc<ThreadBoundInner<T>>,
+}
+
+/// A weak reference to a `ThreadBoundShared<T>`.
+pub struct WeakThreadBoundShared<T> {
+    inner: std::sync::Weak<ThreadBoundInner<T>>,
+}
+
+/// A `LocalInner` that only its owner thread may touch.
+struct ThreadBoundInner<T> {
+    owner: std::thread::ThreadId,
+    /// Dropped with the container, but only on the owner thread.
+    local: std::mem::ManuallyDrop<LocalInner<T>>,
+}
+
+// SAFETY: `local` is only accessed, and only dropped, on the owner thread. The
+// guards borrowing it are not `Send`, so they are released there too.
+unsafe impl<T> Send for ThreadBoundInner<T> {}
+
+// SAFETY: other threads may hold a `&ThreadBoundInner<T>`, but they can only read
+// `owner`. Every path to `local` goes through `ThreadBoundShared::local()`, which
+// returns `AccessError::WrongThread` unless the caller is the owner thread.
+unsafe impl<T> Sync for ThreadBoundInner<T> {}
+
+impl<T> Drop for ThreadBoundInner<T> {
+    fn drop(&mut self) {
+        if std::thread::current().id() == self.owner {
+            // SAFETY: `local` is never used again.
+            unsafe { std::mem::ManuallyDrop::drop(&mut self.local) };
+        }
+    }
+}
+
+/// Borrow flag and data shared by all handles of a `LocalShared<T>`.
+struct LocalInner<T> {
+    lock: lock::BorrowFlag,
+    /// Counts the guards sharing the borrow after `map_split`.
+    holders: lock::Holders,
+    data: UnsafeCell<T>,
+}
+
+impl<T> LocalInner<T> {
+    fn new(value: T) -> Self {
+        LocalInner {
+            lock: lock::BorrowFlag::new(),
+            holders: lock::Holders::default(),
+            data: UnsafeCell::new(value),
+        }
+    }
+
+    /// Borrows the value for `Debug`, reporting a mutable borrow as busy.
+    fn debug_value(&self) -> Result<SyncReadGuard<'_, T>, &'static str> {
+        self.read(AccessError::WouldBlock).map_err(|_| "busy")
+    }
+
+    /// Takes a shared borrow, failing with `conflict` if the value is mutably
+    /// borrowed.
+    fn read(&self, conflict: AccessError) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        if !self.lock.try_lock_shared() {
+            return Err(conflict);
+        }
+        // SAFETY: the shared borrow was just acquired.
+        Ok(unsafe { SyncReadGuard::new(self, self.data.get()) })
+    }
+
+    /// Takes the exclusive borrow, failing with `conflict` if the value is borrowed.
+    #[track_caller]
+    fn write(&self, conflict: AccessError) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        if !self.lock.try_lock_exclusive() {
+            return Err(conflict);
+        }
+        // SAFETY: the exclusive borrow was just acquired.
+        Ok(unsafe { SyncWriteGuard::new(self, self.data.get()) })
+    }
+}
+
+impl<T> LockTarget for LocalInner<T> {
+    fn backend(&self) -> &dyn LockBackend {
+        &self.lock
+    }
+
+    fn holders(&self) -> &lock::Holders {
+        &self.holders
+    }
+
+    /// Like a `RefCell`, the container is not poisoned by a panicking writer.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn poison(&self, _info: PoisonInfo) {}
+
+    fn release_exclusive(&self) {
+        self.lock.unlock_exclusive();
+    }
+
+    fn downgrade(&self) {
+        self.lock.downgrade();
+    }
+
+    fn written(&self) {}
+}
+
+/// An asynchronous shared container using tokio primitives.
+///
+/// The lock and change notifications come from `tokio::sync`, which does not need
+/// the tokio runtime, so the container works on any executor. Only the timed methods
+/// such as [`read_timeout_async`](Self::read_timeout_async) rely on tokio's timer.
+/// Synchronous code outside a runtime can use [`blocking_read`](Self::blocking_read)
+/// and friends.
+///
+/// Only available with the `async` feature flag.
+#[cfg(feature = "async")]
+pub struct AsyncShared<T> {
+    inner: Arc<tokio::sync::RwLock<T>>,
+    /// Bumped by every released write guard. Only strong handles and write guards
+    /// hold it, so snapshot streams end once the container is gone.
+    changes: Arc<tokio::sync::watch::Sender<()>>,
+}
+
+#[cfg(feature = "async")]
+unsafe impl<T: Send> Send for AsyncShared<T> {}
+
+#[cfg(feature = "async")]
+unsafe impl<T: Send + Sync> Sync for AsyncShared<T> {}
+
+/// A weak reference to an `AsyncShared<T>`.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct WeakAsyncShared<T> {
+    inner: Weak<tokio::sync::RwLock<T>>,
+    changes: Weak<tokio::sync::watch::Sender<()>>,
+}
+
+/// An asynchronous shared container built on `tokio::sync::Mutex`.
+///
+/// The async counterpart of [`SharedMutex`]: only `T: Send` is required for the
+/// container to be `Send + Sync`. Only available with the `async` feature flag.
+#[cfg(feature = "async")]
+pub struct AsyncSharedMutex<T> {
+    inner: Arc<tokio::sync::Mutex<T>>,
+}
+
+/// A weak reference to an `AsyncSharedMutex<T>`.
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct WeakAsyncSharedMutex<T> {
+    inner: Weak<tokio::sync::Mutex<T>>,
+}
+
+/// A single-threaded asynchronous shared container.
+///
+/// Built on `Rc` and an async borrow flag: tasks that cannot take the lock wait in a
+/// queue and are woken in order, so a waiting writer is not starved by later
+/// readers. The container is neither `Send` nor `Sync` and places no bounds on `T`,
+/// which makes it the async container for wasm32 and for `!Send` state inside a
+/// `tokio::task::LocalSet`. Access goes through [`LocalAsyncAccess`].
+///
+/// It needs no runtime and no feature flag. Unlike [`Shared`], a panic while
+/// writing does not poison the container.
+pub struct LocalAsyncShared<T> {
+    inner: std::rc::Rc<LocalAsyncInner<T>>,
+}
+
+/// A weak reference to a `LocalAsyncShared<T>`.
+pub struct WeakLocalAsyncShared<T> {
+    inner: std::rc::Weak<LocalAsyncInner<T>>,
+}
+
+struct LocalAsyncInner<T> {
+    lock: lock::LocalAsyncLock,
+    data: UnsafeCell<T>,
+}
+
+impl<T> LocalAsyncInner<T> {
+    /// Wraps the value in a read guard. The caller must hold the lock in shared mode.
+    fn read_guard(&self) -> LocalAsyncReadGuard<'_, T> {
+        LocalAsyncReadGuard {
+            lock: &self.lock,
+            // SAFETY: `UnsafeCell::get` never returns null.
+            data: unsafe { NonNull::new_unchecked(self.data.get()) },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Wraps the value in a write guard. The caller must hold the lock in exclusive
+    /// mode.
+    fn write_guard(&self) -> LocalAsyncWriteGuard<'_, T> {
+        LocalAsyncWriteGuard {
+            lock: &self.lock,
+            // SAFETY: `UnsafeCell::get` never returns null.
+            data: unsafe { NonNull::new_unchecked(self.data.get()) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A universal container that can hold either sync or async variants.
+///
+/// This enum allows writing generic code that works with both sync and async containers,
+/// but requires explicit handling of the mode mismatch via `Result`.
+///
+/// The async variants serve [`SyncAccess::read`], [`SyncAccess::write`] and
+/// [`SyncAccess::get_cloned`] by blocking, as [`AsyncShared::blocking_read`] and
+/// [`AsyncSharedMutex::blocking_lock`] do, and the `try_*` methods without waiting.
+/// [`AsyncAccess`] serves every variant: sync containers are locked without blocking
+/// the executor, like [`Shared`] does on its own, and an `AsyncMutex` hands out
+/// exclusive guards even for reads. On wasm32 nothing wakes a task waiting for the
+/// borrow flag of a sync container, so the task yields and retries until the
+/// conflicting borrow is gone.
+///
+/// Single-threaded containers such as [`LocalShared`] have no variant, so on native
+/// targets `SharedAny<T>` is `Send + Sync` whenever `T` is. [`LocalSharedAny`] adds
+/// a `Local` variant for single-threaded code.
+///
+/// New variants may be added in minor releases, so matches need a wildcard arm.
+#[non_exhaustive]
+pub enum SharedAny<T> {
+    Sync(Shared<T>),
+    Mutex(SharedMutex<T>),
+    #[cfg(feature = "async")]
+    Async(AsyncShared<T>),
+    #[cfg(feature = "async")]
+    AsyncMutex(AsyncSharedMutex<T>),
+}
+
+/// A weak reference to a `SharedAny<T>`.
+///
+/// New variants may be added in minor releases, so matches need a wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WeakSharedAny<T> {
+    Sync(WeakShared<T>),
+    Mutex(WeakSharedMutex<T>),
+    #[cfg(feature = "async")]
+    Async(WeakAsyncShared<T>),
+    #[cfg(feature = "async")]
+    AsyncMutex(WeakAsyncSharedMutex<T>),
+}
+
+/// A universal container for single-threaded code, holding either a [`LocalShared`]
+/// or anything a [`SharedAny`] holds.
+///
+/// The `Local` variant lives here rather than in `SharedAny`, so that `SharedAny`
+/// stays `Send + Sync`. Because of it, `LocalSharedAny` is neither `Send` nor `Sync`.
+/// The `Any` variant behaves exactly like the `SharedAny` it wraps.
+///
+/// New variants may be added in minor releases, so matches need a wildcard arm.
+///
+/// # Examples
+///
+/// ```
+/// use shared_container::{AccessError, LocalShared, LocalSharedAny, Shared, SyncAccess};
+///
+/// let widgets: Vec<LocalSharedAny<u32>> = vec![
+///     LocalShared::new(1).into(),
+///     Shared::new(2).into(),
+/// ];
+/// let reader = widgets[0].read().unwrap();
+/// assert_eq!(widgets[0].write().unwrap_err(), AccessError::BorrowConflict);
+/// drop(reader);
+/// widgets[1].with_mut(|v| *v += 1).unwrap();
+/// ```
+#[non_exhaustive]
+pub enum LocalSharedAny<T> {
+    Local(LocalShared<T>),
+    Any(SharedAny<T>),
+}
+
+/// A weak reference to a `LocalSharedAny<T>`.
+///
+/// New variants may be added in minor releases, so matches need a wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WeakLocalSharedAny<T> {
+    Local(WeakLocalShared<T>),
+    Any(WeakSharedAny<T>),
+}
+
+// ============================================================================
+// Basic constructors and conversions
+// ============================================================================
+
+impl<T> Shared<T> {
+    /// Creates a new synchronous shared container.
+    pub fn new(value: T) -> Self {
+        Self::with_poison_policy(value, PoisonPolicy::Propagate)
+    }
+
+    /// Creates a new synchronous shared container with the given poison policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{PoisonPolicy, Shared, SyncAccess};
+    ///
+    /// let counter = Shared::with_poison_policy(0, PoisonPolicy::reset_to_default());
+    /// let other = counter.clone();
+    /// let _ = std::thread::spawn(move || {
+    ///     let mut guard = other.write().unwrap();
+    ///     *guard = 42;
+    ///     panic!("handler failed");
+    /// })
+    /// .join();
+    ///
+    /// assert_eq!(counter.get_cloned().unwrap(), 0);
+    /// ```
+    pub fn with_poison_policy(value: T, policy: PoisonPolicy<T>) -> Self {
+        Self::with_backend_and_poison_policy(value, policy)
+    }
+}
+
+impl<T, B: LockBackend> Shared<T, B> {
+    /// Creates a new synchronous shared container that locks with the backend `B`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{DefaultBackend, Shared, SyncAccess};
+    ///
+    /// let counter = Shared::<_, DefaultBackend>::with_backend(0);
+    /// *counter.write().unwrap() += 1;
+    /// assert_eq!(counter.get_cloned().unwrap(), 1);
+    /// ```
+    pub fn with_backend(value: T) -> Self {
+        Self::with_backend_and_poison_policy(value, PoisonPolicy::Propagate)
+    }
+
+    /// Creates a new synchronous shared container that locks with the backend `B`
+    /// and uses the given poison policy.
+    pub fn with_backend_and_poison_policy(value: T, policy: PoisonPolicy<T>) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Shared {
+                inner: std::rc::Rc::new(SharedInner::new(value, policy)),
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Shared {
+                inner: std::sync::Arc::new(SharedInner::new(value, policy)),
+            }
+        }
+    }
+
+    /// Creates a weak reference to this container.
+    pub fn downgrade(&self) -> WeakShared<T, B> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            WeakShared {
+                inner: std::rc::Rc::downgrade(&self.inner),
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            WeakShared {
+                inner: std::sync::Arc::downgrade(&self.inner),
+            }
+        }
+    }
+
+    /// Returns the value if this is the only strong handle, or the handle otherwise.
+    ///
+    /// Weak references do not count; they fail to upgrade afterwards, and observers
+    /// are dropped with the container. A poisoned container is returned as well
+    /// unless its policy ignores poisoning; clear the poison first to take the value.
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        // Holding the lock keeps a handle upgraded from a weak reference from
+        // poisoning the container between the check and the unwrap. A held lock
+        // means another handle exists anyway.
+        if !self.inner.lock.try_lock_exclusive() {
+            return Err(self);
+        }
+        if self.inner.refuses_access() {
+            self.inner.lock.unlock_exclusive();
+            return Err(self);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        let inner = std::rc::Rc::try_unwrap(self.inner);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let inner = std::sync::Arc::try_unwrap(self.inner);
+
+        match inner {
+            Ok(inner) => {
+                inner.lock.unlock_exclusive();
+                Ok(inner.data.into_inner())
+            }
+            Err(inner) => {
+                inner.lock.unlock_exclusive();
+                Err(Shared { inner })
+            }
+        }
+    }
+
+    /// Returns the value if this is the only strong handle, dropping the handle
+    /// otherwise.
+    ///
+    /// Unlike [`try_unwrap`](Self::try_unwrap), when the last handles are dropped
+    /// concurrently exactly one of them gets the value. Unless the policy ignores
+    /// poisoning, a poisoned value is returned inside a [`PoisonError`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::Shared;
+    ///
+    /// let state = Shared::new(vec![1]);
+    /// let other = state.clone();
+    /// assert!(state.into_inner().unwrap().is_none());
+    /// assert_eq!(other.into_inner().unwrap(), Some(vec![1]));
+    /// ```
+    pub fn into_inner(self) -> Result<Option<T>, PoisonError<T>> {
+        #[cfg(target_arch = "wasm32")]
+        let inner = std::rc::Rc::into_inner(self.inner);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let inner = std::sync::Arc::into_inner(self.inner);
+
+        let Some(inner) = inner else {
+            return Ok(None);
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if inner.refuses_access()
+            && let Some(info) = inner.poison.info()
+        {
+            return Err(PoisonError {
+                guard: inner.data.into_inner(),
+                info,
+            });
+        }
+        Ok(Some(inner.data.into_inner()))
+    }
+
+    /// Returns a mutable reference to the value without locking.
+    ///
+    /// Needs the only handle: returns `AccessError::NotUnique` while other handles,
+    /// weak references or subscriptions exist. Fails with `AccessError::Poisoned`
+    /// like [`read`](SyncAccess::read) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{AccessError, Shared};
+    ///
+    /// let mut counter = Shared::new(0);
+    /// *counter.get_mut().unwrap() += 1;
+    ///
+    /// let other = counter.clone();
+    /// assert_eq!(counter.get_mut().unwrap_err(), AccessError::NotUnique);
+    /// drop(other);
+    /// assert_eq!(*counter.get_mut().unwrap(), 1);
+    /// ```
+    pub fn get_mut(&mut self) -> Result<&mut T, AccessError> {
+        #[cfg(target_arch = "wasm32")]
+        let inner = std::rc::Rc::get_mut(&mut self.inner);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let inner = std::sync::Arc::get_mut(&mut self.inner);
+
+        let inner = inner.ok_or(AccessError::NotUnique)?;
+        if inner.refuses_access() {
+            return Err(AccessError::Poisoned);
+        }
+        Ok(inner.data.get_mut())
+    }
+
+    /// Returns a mutable reference to the value, first moving this handle to a
+    /// clone of the value if other handles or weak references exist.
+    ///
+    /// The clone is taken under a read lock, so it fails like
+    /// [`get_cloned`](SyncAccess::get_cloned). The new container starts without
+    /// observers. It keeps a `Propagate` or `Ignore` poison policy, but since a
+    /// `Reset` factory cannot be copied it falls back to `Propagate` then.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{Shared, SyncAccess};
+    ///
+    /// let mut snapshot = Shared::new(vec![1, 2]);
+    /// let live = snapshot.clone();
+    /// snapshot.make_mut().unwrap().push(3);
+    ///
+    /// assert_eq!(snapshot.get_cloned().unwrap(), vec![1, 2, 3]);
+    /// assert_eq!(live.get_cloned().unwrap(), vec![1, 2]);
+    /// ```
+    pub fn make_mut(&mut self) -> Result<&mut T, AccessError>
+    where
+        T: Clone,
+    {
+        #[cfg(target_arch = "wasm32")]
+        let unique = std::rc::Rc::get_mut(&mut self.inner).is_some();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let unique = std::sync::Arc::get_mut(&mut self.inner).is_some();
+
+        if !unique {
+            let value = self.get_cloned()?;
+            let policy = match self.inner.policy {
+                PoisonPolicy::Ignore => PoisonPolicy::Ignore,
+                PoisonPolicy::Propagate | PoisonPolicy::Reset(_) => PoisonPolicy::Propagate,
+            };
+            *self = Self::with_backend_and_poison_policy(value, policy);
+        }
+        self.get_mut()
+    }
+
+    /// Moves the value into an [`AsyncShared`] if this is the only strong handle,
+    /// or returns the handle otherwise.
+    ///
+    /// The value is moved, not cloned, and observers stay behind. A poisoned
+    /// container is returned as by [`try_unwrap`](Self::try_unwrap).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{Shared, SyncAccess};
+    ///
+    /// let config = Shared::new(vec![String::from("db")]);
+    /// config.write().unwrap().push(String::from("cache"));
+    ///
+    /// let config = config.into_async().unwrap();
+    /// assert_eq!(config.blocking_read().unwrap().len(), 2);
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn into_async(self) -> Result<AsyncShared<T>, Self> {
+        self.try_unwrap().map(AsyncShared::new)
+    }
+
+    /// Acquires a read lock, giving up after `timeout`.
+    ///
+    /// Returns `AccessError::Timeout` if the lock could not be acquired in time.
+    ///
+    /// On wasm32 a conflicting borrow can never be released while the caller
+    /// waits, so conflicts are reported immediately as `AccessError::BorrowConflict`.
+    pub fn read_timeout(&self, timeout: Duration) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match Instant::now().checked_add(timeout) {
+                Some(deadline) => self.read_until(deadline),
+                None => self.read(),
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = timeout;
+            self.read()
+        }
+    }
+
+    /// Acquires a write lock, giving up after `timeout`.
+    ///
+    /// Returns `AccessError::Timeout` if the lock could not be acquired in time.
+    ///
+    /// On wasm32 a conflicting borrow can never be released while the caller
+    /// waits, so conflicts are reported immediately as `AccessError::BorrowConflict`.
+    #[track_caller]
+    pub fn write_timeout(&self, timeout: Duration) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match Instant::now().checked_add(timeout) {
+                Some(deadline) => self.write_until(deadline),
+                None => self.write(),
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = timeout;
+            self.write()
+        }
+    }
+
+    /// Acquires a read lock, giving up once `deadline` has passed.
+    ///
+    /// Returns `AccessError::Timeout` if the lock could not be acquired in time.
+    /// See [`read_timeout`](Self::read_timeout) for wasm32 behavior.
+    pub fn read_until(&self, deadline: Instant) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if !self.inner.lock.lock_shared_until(deadline) {
+                return Err(AccessError::Timeout);
+            }
+            self.acquired_read()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = deadline;
+            self.read()
+        }
+    }
+
+    /// Acquires a write lock, giving up once `deadline` has passed.
+    ///
+    /// Returns `AccessError::Timeout` if the lock could not be acquired in time.
+    /// See [`write_timeout`](Self::write_timeout) for wasm32 behavior.
+    #[track_caller]
+    pub fn write_until(&self, deadline: Instant) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if !self.inner.lock.lock_exclusive_until(deadline) {
+                return Err(AccessError::Timeout);
+            }
+            self.acquired_write()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = deadline;
+            self.write()
+        }
+    }
+
+    /// Blocks until `predicate` holds for the current value.
+    ///
+    /// The predicate is checked against the current value and then again after every
+    /// completed write. Returns a read guard taken while it held, so the value cannot
+    /// change before the caller looks at it. Fails like [`read`](SyncAccess::read),
+    /// for example if a writer panicked while the caller was waiting.
+    ///
+    /// On wasm32 no other code can run while the caller blocks, so an unmet predicate
+    /// is reported immediately as `AccessError::WouldBlock`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{Shared, SyncAccess};
+    ///
+    /// let ready = Shared::new(false);
+    /// let setter = ready.clone();
+    /// std::thread::spawn(move || setter.replace(true));
+    ///
+    /// let guard = ready.wait_until(|ready| *ready).unwrap();
+    /// assert!(*guard);
+    /// ```
+    pub fn wait_until<F>(&self, predicate: F) -> Result<SyncReadGuard<'_, T>, AccessError>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.wait_until_inner(None, predicate)
+    }
+
+    /// Blocks until `predicate` holds for the current value, giving up after `timeout`.
+    ///
+    /// Returns `AccessError::Timeout` if the predicate did not hold in time. See
+    /// [`wait_until`](Self::wait_until) for details and wasm32 behavior.
+    pub fn wait_until_timeout<F>(
+        &self,
+        timeout: Duration,
+        predicate: F,
+    ) -> Result<SyncReadGuard<'_, T>, AccessError>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.wait_until_inner(Instant::now().checked_add(timeout), predicate)
+    }
+
+    fn wait_until_inner<F>(
+        &self,
+        deadline: Option<Instant>,
+        mut predicate: F,
+    ) -> Result<SyncReadGuard<'_, T>, AccessError>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        loop {
+            let guard = match deadline {
+                Some(deadline) => self.read_until(deadline)?,
+                None => self.read()?,
+            };
+            if predicate(&guard) {
+                return Ok(guard);
+            }
+            // Read while no write can be in progress, so none is missed after the
+            // guard is dropped.
+            let seen = self.inner.signal.writes();
+            drop(guard);
+            if !self.inner.signal.wait(seen, deadline) {
+                return Err(AccessError::Timeout);
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = deadline;
+            let guard = self.read()?;
+            if predicate(&guard) {
+                Ok(guard)
+            } else {
+                Err(AccessError::WouldBlock)
+            }
+        }
+    }
+
+    /// Acquires an upgradable read lock.
+    ///
+    /// The returned guard coexists with plain readers but excludes writers and other
+    /// upgradable readers. Upgrade it with [`SyncUpgradableReadGuard::upgrade`] to
+    /// modify the data without another writer getting in first.
+    ///
+    /// On wasm32 a conflicting borrow is reported as `AccessError::BorrowConflict`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use shared_container::{Shared, SyncUpgradableReadGuard};
+    ///
+    /// let cache = Shared::new(HashMap::new());
+    /// let entry = cache.upgradable_read().unwrap();
+    /// if !entry.contains_key("answer") {
+    ///     let mut entry = SyncUpgradableReadGuard::upgrade(entry).unwrap();
+    ///     entry.insert("answer", 42);
+    /// }
+    /// ```
+    pub fn upgradable_read(&self) -> Result<SyncUpgradableReadGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.inner.lock.lock_upgradable();
+            self.acquired_upgradable()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if !self.inner.lock.try_lock_upgradable() {
+                return Err(AccessError::BorrowConflict);
+            }
+            self.acquired_upgradable()
+        }
+    }
+
+    /// Attempts to acquire an upgradable read lock without blocking.
+    ///
+    /// Returns `AccessError::WouldBlock` if a writer or another upgradable reader
+    /// holds the lock.
+    pub fn try_upgradable_read(&self) -> Result<SyncUpgradableReadGuard<'_, T>, AccessError> {
+        if !self.inner.lock.try_lock_upgradable() {
+            return Err(AccessError::WouldBlock);
+        }
+        self.acquired_upgradable()
+    }
+
+    /// Acquires a read lock that keeps this container alive.
+    ///
+    /// Behaves like [`read`](SyncAccess::read), but the returned guard holds a strong
+    /// reference instead of borrowing `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{OwnedSyncReadGuard, Shared};
+    ///
+    /// fn load() -> OwnedSyncReadGuard<Vec<u32>> {
+    ///     Shared::new(vec![1, 2, 3]).read_owned().unwrap()
+    /// }
+    ///
+    /// assert_eq!(load().len(), 3);
+    /// ```
+    pub fn read_owned(&self) -> Result<OwnedSyncReadGuard<T, T, B>, AccessError> {
+        let guard = self.read()?;
+        Ok(OwnedSyncReadGuard::from_guard(self.clone(), guard))
+    }
+
+    /// Acquires a write lock that keeps this container alive.
+    ///
+    /// Behaves like [`write`](SyncAccess::write), but the returned guard holds a
+    /// strong reference instead of borrowing `self`.
+    #[track_caller]
+    pub fn write_owned(&self) -> Result<OwnedSyncWriteGuard<T, B>, AccessError> {
+        let guard = self.write()?;
+        Ok(OwnedSyncWriteGuard::from_guard(self.clone(), guard))
+    }
+
+    /// Attempts to acquire an owned read lock without blocking.
+    ///
+    /// See [`try_read`](SyncAccess::try_read).
+    pub fn try_read_owned(&self) -> Result<OwnedSyncReadGuard<T, T, B>, AccessError> {
+        let guard = self.try_read()?;
+        Ok(OwnedSyncReadGuard::from_guard(self.clone(), guard))
+    }
+
+    /// Attempts to acquire an owned write lock without blocking.
+    ///
+    /// See [`try_write`](SyncAccess::try_write).
+    #[track_caller]
+    pub fn try_write_owned(&self) -> Result<OwnedSyncWriteGuard<T, B>, AccessError> {
+        let guard = self.try_write()?;
+        Ok(OwnedSyncWriteGuard::from_guard(self.clone(), guard))
+    }
+
+    /// Acquires a read lock, handing the guard back if the container is poisoned.
+    ///
+    /// Unlike [`read`](SyncAccess::read), a poisoned container reports a
+    /// [`PoisonError`] that still holds the guard, so the data can be inspected
+    /// anyway. On wasm32 a conflicting borrow is reported as
+    /// `AccessError::BorrowConflict`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{RecoverableError, Shared, SyncAccess};
+    ///
+    /// let container = Shared::new(vec![1, 2]);
+    /// let other = container.clone();
+    /// let _ = std::thread::spawn(move || {
+    ///     other.write().unwrap().push(3);
+    ///     let _guard = other.write().unwrap();
+    ///     panic!("writer failed");
+    /// })
+    /// .join();
+    ///
+    /// let guard = match container.read_recoverable() {
+    ///     Ok(guard) => guard,
+    ///     Err(RecoverableError::Poisoned(err)) => err.into_inner(),
+    ///     Err(RecoverableError::Access(err)) => panic!("{err}"),
+    /// };
+    /// assert_eq!(*guard, [1, 2, 3]);
+    /// ```
+    pub fn read_recoverable(
+        &self,
+    ) -> Result<SyncReadGuard<'_, T>, RecoverableError<SyncReadGuard<'_, T>>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.inner.lock.lock_shared();
+
+        #[cfg(target_arch = "wasm32")]
+        if !self.inner.lock.try_lock_shared() {
+            return Err(RecoverableError::Access(AccessError::BorrowConflict));
+        }
+
+        // SAFETY: the shared lock was just acquired.
+        let guard = unsafe { SyncReadGuard::new(&*self.inner, self.inner.data.get()) };
+        self.checked(guard)
+    }
+
+    /// Acquires a write lock, handing the guard back if the container is poisoned.
+    ///
+    /// See [`read_recoverable`](Self::read_recoverable). Writing through the
+    /// recovered guard does not clear the poisoning; call
+    /// [`clear_poison`](Self::clear_poison) once the data is consistent again.
+    #[track_caller]
+    pub fn write_recoverable(
+        &self,
+    ) -> Result<SyncWriteGuard<'_, T>, RecoverableError<SyncWriteGuard<'_, T>>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.inner.lock.lock_exclusive();
+
+        #[cfg(target_arch = "wasm32")]
+        if !self.inner.lock.try_lock_exclusive() {
+            return Err(RecoverableError::Access(AccessError::BorrowConflict));
+        }
+
+        // SAFETY: the exclusive lock was just acquired.
+        let guard = unsafe { SyncWriteGuard::new(&*self.inner, self.inner.data.get()) };
+        self.checked(guard)
+    }
+
+    /// Returns whether a panic while holding a write lock has poisoned the container.
+    ///
+    /// Always `false` on wasm32.
+    pub fn is_poisoned(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.inner.poison.is_poisoned()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            false
+        }
+    }
+
+    /// Describes the panic that poisoned the container, if it is poisoned.
+    pub fn poison_info(&self) -> Option<PoisonInfo> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.inner.poison.info()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            None
+        }
+    }
+
+    /// Clears the poisoned state, making the container accessible again.
+    ///
+    /// Only call this once the data is known to be consistent, for example after
+    /// repairing it through [`write_recoverable`](Self::write_recoverable).
+    pub fn clear_poison(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.inner.poison.clear();
+    }
+
+    /// Registers `observer` to run after every write.
+    ///
+    /// The observer runs with the new value each time the last guard of a write lock
+    /// is released (including when a write guard is downgraded), on the thread that
+    /// released it. Writes abandoned by a panic are not reported. Dropping the
+    /// returned [`Subscription`] unsubscribes the observer.
+    ///
+    /// Observers run while the container is locked for reading. They must not write
+    /// to it, and on native targets reading it again may deadlock with a waiting
+    /// writer; use the value passed in instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use shared_container::{Shared, SyncAccess};
+    ///
+    /// let config = Shared::new(String::from("v1"));
+    /// let seen = Arc::new(Mutex::new(Vec::new()));
+    ///
+    /// let log = seen.clone();
+    /// let subscription = config.subscribe(move |value: &String| {
+    ///     log.lock().unwrap().push(value.clone());
+    /// });
+    ///
+    /// config.replace(String::from("v2")).unwrap();
+    /// drop(subscription);
+    /// config.replace(String::from("v3")).unwrap();
+    ///
+    /// assert_eq!(*seen.lock().unwrap(), ["v2"]);
+    /// ```
+    pub fn subscribe<F>(&self, observer: F) -> Subscription
+    where
+        T: MaybeSendSync + 'static,
+        F: Fn(&T) + MaybeSendSync + 'static,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        let observer: Observer<T> = std::sync::Arc::new(observer);
+
+        #[cfg(target_arch = "wasm32")]
+        let observer: Observer<T> = std::rc::Rc::new(observer);
+
+        let id = self.inner.observers.add(observer);
+        let registry: RegistryRef = self.downgrade().inner;
+        Subscription { registry, id }
+    }
+
+    /// Registers `observer` to run after writes that change the value.
+    ///
+    /// Like [`subscribe`](Self::subscribe), but the observer only runs when the new
+    /// value differs from the one it last saw, starting with the current value. Fails
+    /// if the current value cannot be read.
+    pub fn subscribe_changes<F>(&self, observer: F) -> Result<Subscription, AccessError>
+    where
+        T: Clone + PartialEq + MaybeSendSync + 'static,
+        F: Fn(&T) + MaybeSendSync + 'static,
+    {
+        // Holding the read lock while subscribing means no write can slip in between
+        // taking the snapshot and registering the observer.
+        let guard = self.read()?;
+        let last = std::sync::Mutex::new((*guard).clone());
+        let subscription = self.subscribe(move |value: &T| {
+            let mut last = last
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if *last != *value {
+                *last = value.clone();
+                drop(last);
+                observer(value);
+            }
+        });
+        drop(guard);
+        Ok(subscription)
+    }
+
+    /// Wraps an already acquired read lock, failing if the container is poisoned.
+    fn acquired_read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        // SAFETY: the caller has just acquired the shared lock.
+        let guard = unsafe { SyncReadGuard::new(&*self.inner, self.inner.data.get()) };
+        self.checked(guard).map_err(AccessError::from)
+    }
+
+    /// Wraps an already acquired write lock, failing if the container is poisoned.
+    #[track_caller]
+    fn acquired_write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        // SAFETY: the caller has just acquired the exclusive lock.
+        let guard = unsafe { SyncWriteGuard::new(&*self.inner, self.inner.data.get()) };
+        self.checked(guard).map_err(AccessError::from)
+    }
+
+    /// Wraps an already acquired upgradable lock, failing if the container is poisoned.
+    fn acquired_upgradable(&self) -> Result<SyncUpgradableReadGuard<'_, T>, AccessError> {
+        // SAFETY: the caller has just acquired the upgradable lock.
+        let guard = unsafe { SyncUpgradableReadGuard::new(&*self.inner, self.inner.data.get()) };
+        self.checked(guard).map_err(AccessError::from)
+    }
+
+    /// Applies the poison policy to a freshly acquired guard.
+    ///
+    /// Dropping the guard inside the error releases the lock.
+    fn checked<G>(&self, guard: G) -> Result<G, RecoverableError<G>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.inner.poison.is_poisoned()
+            && !matches!(self.inner.policy, PoisonPolicy::Ignore)
+            && let Some(info) = self.inner.poison.info()
+        {
+            return Err(RecoverableError::Poisoned(PoisonError { guard, info }));
+        }
+        Ok(guard)
+    }
+}
+
+impl<T, B> Clone for Shared<T, B> {
+    fn clone(&self) -> Self {
+        #[cfg(target_arch = "wasm32")]
+        {
+            Shared {
+                inner: std::rc::Rc::clone(&self.inner),
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Shared {
+                inner: std::sync::Arc::clone(&self.inner),
+            }
+        }
+    }
+}
+
+impl<T, B: LockBackend> Shared<T, B> {
+    /// Reads the value for `Debug` without blocking or recovering from poisoning.
+    fn debug_view(&self, name: &'static str) -> DebugContainer<SyncReadGuard<'_, T>> {
+        let value = if self.inner.refuses_access() {
+            Err("poisoned")
+        } else if self.inner.lock.try_lock_shared() {
+            // SAFETY: the shared lock is held until the guard is dropped.
+            Ok(unsafe { SyncReadGuard::new(&*self.inner, self.inner.data.get()) })
+        } else {
+            Err("busy")
+        };
+        DebugContainer {
+            name,
+            value,
+            strong: self.strong_count(),
+            weak: self.weak_count(),
+        }
+    }
+}
+
+/// Shows the value if it can be read without blocking, and otherwise the lock state
+/// with the handle counts: `<poisoned>`, or `<busy>` while a writer holds the lock
+/// or, on a backend that prefers writers, waits for it. The other containers print
+/// their state the same way.
+impl<T: std::fmt::Debug, B: LockBackend> std::fmt::Debug for Shared<T, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.debug_view("Shared").fmt(f)
+    }
+}
+
+impl<T, B> WeakShared<T, B> {
+    /// Attempts to upgrade the weak reference to a strong reference.
+    pub fn upgrade(&self) -> Option<Shared<T, B>> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.inner.upgrade().map(|inner| Shared { inner })
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.inner.upgrade().map(|inner| Shared { inner })
+        }
+    }
+}
+
+impl<T, B> Clone for WeakShared<T, B> {
+    fn clone(&self) -> Self {
+        WeakShared {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+// ============================================================================
+// SyncAccess implementation for Shared<T, B>
+// ============================================================================
+
+impl<T, B: LockBackend> SyncAccess<T> for Shared<T, B> {
+    fn read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.inner.lock.lock_shared();
+            self.acquired_read()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if !self.inner.lock.try_lock_shared() {
+                return Err(AccessError::BorrowConflict);
+            }
+            self.acquired_read()
+        }
+    }
+
+    #[track_caller]
+    fn write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.inner.lock.lock_exclusive();
+            self.acquired_write()
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            if !self.inner.lock.try_lock_exclusive() {
+                return Err(AccessError::BorrowConflict);
+            }
+            self.acquired_write()
+        }
+    }
+
+    fn try_read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        if !self.inner.lock.try_lock_shared() {
+            return Err(AccessError::WouldBlock);
+        }
+        self.acquired_read()
+    }
+
+    #[track_caller]
+    fn try_write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        if !self.inner.lock.try_lock_exclusive() {
+            return Err(AccessError::WouldBlock);
+        }
+        self.acquired_write()
+    }
+
+    #[track_caller]
+    fn swap(&self, other: &Self) -> Result<(), AccessError> {
+        let this: *const SharedInner<T, B> = &*self.inner;
+        let that: *const SharedInner<T, B> = &*other.inner;
+        if std::ptr::eq(this, that) {
+            return Ok(());
+        }
+
+        // Lock in address order so concurrent swaps of the same pair cannot deadlock.
+        let (first, second) = if this < that {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut first = first.write()?;
+        let mut second = second.write()?;
+        std::mem::swap(&mut *first, &mut *second);
+        Ok(())
+    }
+
+    fn get_cloned(&self) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        let guard = self.read()?;
+        Ok((*guard).clone())
+    }
+}
+
+// ============================================================================
+// SharedMutex
+// ============================================================================
+
+impl<T> SharedMutex<T> {
+    /// Creates a new mutex-backed shared container.
+    pub fn new(value: T) -> Self {
+        Self::with_poison_policy(value, PoisonPolicy::Propagate)
+    }
+
+    /// Creates a new mutex-backed shared container with the given poison policy.
+    pub fn with_poison_policy(value: T, policy: PoisonPolicy<T>) -> Self {
+        SharedMutex {
+            inner: Shared::with_backend_and_poison_policy(value, policy),
+        }
+    }
+
+    /// Creates a weak reference to this container.
+    pub fn downgrade(&self) -> WeakSharedMutex<T> {
+        WeakSharedMutex {
+            inner: self.inner.downgrade(),
+        }
+    }
+
+    /// Acquires the lock, blocking until it is available.
+    ///
+    /// On wasm32 a held lock is reported as `AccessError::BorrowConflict`.
+    #[track_caller]
+    pub fn lock(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.inner.write()
+    }
+
+    /// Acquires the lock if that is possible without blocking.
+    ///
+    /// Returns `AccessError::WouldBlock` if the lock is held.
+    #[track_caller]
+    pub fn try_lock(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.inner.try_write()
+    }
+
+    /// Acquires the lock, giving up after `timeout`.
+    ///
+    /// See [`Shared::write_timeout`].
+    #[track_caller]
+    pub fn lock_timeout(&self, timeout: Duration) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.inner.write_timeout(timeout)
+    }
+
+    /// Returns whether a panic while holding the lock has poisoned the container.
+    ///
+    /// Always `false` on wasm32.
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.is_poisoned()
+    }
+
+    /// Clears the poisoned state, making the container accessible again.
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison();
+    }
+
+    /// Returns the value if this is the only strong handle, or the handle otherwise.
+    ///
+    /// See [`Shared::try_unwrap`].
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        self.inner
+            .try_unwrap()
+            .map_err(|inner| SharedMutex { inner })
+    }
+
+    /// Returns the value if this is the only strong handle, dropping the handle
+    /// otherwise.
+    ///
+    /// See [`Shared::into_inner`].
+    pub fn into_inner(self) -> Result<Option<T>, PoisonError<T>> {
+        self.inner.into_inner()
+    }
+
+    /// Returns a mutable reference to the value without locking.
+    ///
+    /// See [`Shared::get_mut`].
+    pub fn get_mut(&mut self) -> Result<&mut T, AccessError> {
+        self.inner.get_mut()
+    }
+
+    /// Returns a mutable reference to the value, first moving this handle to a
+    /// clone of the value if other handles exist.
+    ///
+    /// See [`Shared::make_mut`].
+    pub fn make_mut(&mut self) -> Result<&mut T, AccessError>
+    where
+        T: Clone,
+    {
+        self.inner.make_mut()
+    }
+}
+
+impl<T> Clone for SharedMutex<T> {
+    fn clone(&self) -> Self {
+        SharedMutex {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for SharedMutex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.debug_view("SharedMutex").fmt(f)
+    }
+}
+
+impl<T> WeakSharedMutex<T> {
+    /// Attempts to upgrade the weak reference to a strong reference.
+    pub fn upgrade(&self) -> Option<SharedMutex<T>> {
+        self.inner.upgrade().map(|inner| SharedMutex { inner })
+    }
+}
+
+impl<T> Clone for WeakSharedMutex<T> {
+    fn clone(&self) -> Self {
+        WeakSharedMutex {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+// Read and write guards both hold the lock exclusively.
+impl<T> SyncAccess<T> for SharedMutex<T> {
+    fn read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        self.inner.read()
+    }
+
+    #[track_caller]
+    fn write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.inner.write()
+    }
+
+    fn try_read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        self.inner.try_read()
+    }
+
+    #[track_caller]
+    fn try_write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.inner.try_write()
+    }
+
+    #[track_caller]
+    fn swap(&self, other: &Self) -> Result<(), AccessError> {
+        self.inner.swap(&other.inner)
+    }
+
+    fn get_cloned(&self) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        self.inner.get_cloned()
+    }
+}
+
+// ============================================================================
+// LocalShared
+// ============================================================================
+
+impl<T> LocalShared<T> {
+    /// Creates a new single-threaded shared container.
+    pub fn new(value: T) -> Self {
+        LocalShared {
+            inner: std::rc::Rc::new(LocalInner::new(value)),
+        }
+    }
+
+    /// Creates a weak reference to this container.
+    pub fn downgrade(&self) -> WeakLocalShared<T> {
+        WeakLocalShared {
+            inner: std::rc::Rc::downgrade(&self.inner),
+        }
+    }
+
+    /// Returns the value if this is the only strong handle, or the handle otherwise.
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        std::rc::Rc::try_unwrap(self.inner)
+            .map(|inner| inner.data.into_inner())
+            .map_err(|inner| LocalShared { inner })
+    }
+
+    /// Returns the value if this is the only strong handle, dropping the handle
+    /// otherwise.
+    pub fn into_inner(self) -> Option<T> {
+        std::rc::Rc::into_inner(self.inner).map(|inner| inner.data.into_inner())
+    }
+
+    /// Returns a mutable reference to the value without borrowing it.
+    ///
+    /// Returns `AccessError::NotUnique` while other handles or weak references
+    /// exist.
+    pub fn get_mut(&mut self) -> Result<&mut T, AccessError> {
+        std::rc::Rc::get_mut(&mut self.inner)
+            .map(|inner| inner.data.get_mut())
+            .ok_or(AccessError::NotUnique)
+    }
+
+    /// Returns a mutable reference to the value, first moving this handle to a
+    /// clone of the value if other handles or weak references exist.
+    ///
+    /// Cloning fails with `AccessError::BorrowConflict` while another handle
+    /// writes.
+    pub fn make_mut(&mut self) -> Result<&mut T, AccessError>
+    where
+        T: Clone,
+    {
+        if std::rc::Rc::get_mut(&mut self.inner).is_none() {
+            *self = LocalShared::new(self.get_cloned()?);
+        }
+        self.get_mut()
+    }
+}
+
+impl<T> Clone for LocalShared<T> {
+    fn clone(&self) -> Self {
+        LocalShared {
+            inner: std::rc::Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> LocalShared<T> {
+    /// Reads the value for `Debug` without panicking on a conflicting borrow.
+    fn debug_view(&self) -> DebugContainer<SyncReadGuard<'_, T>> {
+        DebugContainer {
+            name: "LocalShared",
+            value: self.inner.debug_value(),
+            strong: self.strong_count(),
+            weak: self.weak_count(),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for LocalShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.debug_view().fmt(f)
+    }
+}
+
+impl<T> WeakLocalShared<T> {
+    /// Attempts to upgrade the weak reference to a strong reference.
+    pub fn upgrade(&self) -> Option<LocalShared<T>> {
+        self.inner.upgrade().map(|inner| LocalShared { inner })
+    }
+}
+
+impl<T> Clone for WeakLocalShared<T> {
+    fn clone(&self) -> Self {
+        WeakLocalShared {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+// Nothing can release a conflicting borrow while the caller waits, so `read` and
+// `write` fail right away, as `Shared` does on wasm32.
+impl<T> SyncAccess<T> for LocalShared<T> {
+    fn read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        self.inner.read(AccessError::BorrowConflict)
+    }
+
+    #[track_caller]
+    fn write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.inner.write(AccessError::BorrowConflict)
+    }
+
+    fn try_read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        self.inner.read(AccessError::WouldBlock)
+    }
+
+    #[track_caller]
+    fn try_write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.inner.write(AccessError::WouldBlock)
+    }
+
+    #[track_caller]
+    fn swap(&self, other: &Self) -> Result<(), AccessError> {
+        if std::rc::Rc::ptr_eq(&self.inner, &other.inner) {
+            return Ok(());
+        }
+        let mut this = self.write()?;
+        let mut that = other.write()?;
+        std::mem::swap(&mut *this, &mut *that);
+        Ok(())
+    }
+
+    fn get_cloned(&self) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        let guard = self.read()?;
+        Ok((*guard).clone())
+    }
+}
+
+// ============================================================================
+// ThreadBoundShared
+// ============================================================================
+
+impl<T> ThreadBoundShared<T> {
+    /// Creates a new container owned by the current thread.
+    pub fn new(value: T) -> Self {
+        ThreadBoundShared {
+            inner: std::sync::Arc::new(ThreadBoundInner {
+                owner: std::thread::current().id(),
+                local: std::mem::ManuallyDrop::new(LocalInner::new(value)),
+            }),
+        }
+    }
+
+    /// Creates a weak reference to this container.
+    pub fn downgrade(&self) -> WeakThreadBoundShared<T> {
+        WeakThreadBoundShared {
+            inner: std::sync::Arc::downgrade(&self.inner),
+        }
+    }
+
+    /// Returns the thread that may access the value.
+    pub fn owner_thread(&self) -> std::thread::ThreadId {
+        self.inner.owner
+    }
+
+    /// Returns the value's borrow flag and data, if called on the owner thread.
+    fn local(&self) -> Result<&LocalInner<T>, AccessError> {
+        if std::thread::current().id() == self.inner.owner {
+            Ok(&self.inner.local)
+        } else {
+            Err(AccessError::WrongThread)
+        }
+    }
+}
+
+impl<T> Clone for ThreadBoundShared<T> {
+    fn clone(&self) -> Self {
+        ThreadBoundShared {
+            inner: std::sync::Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for ThreadBoundShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        DebugContainer {
+            name: "ThreadBoundShared",
+            value: self
+                .local()
+                .map_err(|_| "other thread")
+                .and_then(LocalInner::debug_value),
+            strong: self.strong_count(),
+            weak: self.weak_count(),
+        }
+        .fmt(f)
+    }
+}
+
+impl<T> WeakThreadBoundShared<T> {
+    /// Attempts to upgrade the weak reference to a strong reference.
+    pub fn upgrade(&self) -> Option<ThreadBoundShared<T>> {
+        self.inner
+            .upgrade()
+            .map(|inner| ThreadBoundShared { inner })
+    }
+}
+
+impl<T> Clone for WeakThreadBoundShared<T> {
+    fn clone(&self) -> Self {
+        WeakThreadBoundShared {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for WeakThreadBoundShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakThreadBoundShared")
+            .finish_non_exhaustive()
+    }
+}
+
+// As for `LocalShared`, conflicting borrows fail right away.
+impl<T> SyncAccess<T> for ThreadBoundShared<T> {
+    fn read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        self.local()?.read(AccessError::BorrowConflict)
+    }
+
+    #[track_caller]
+    fn write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.local()?.write(AccessError::BorrowConflict)
+    }
+
+    fn try_read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        self.local()?.read(AccessError::WouldBlock)
+    }
+
+    #[track_caller]
+    fn try_write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        self.local()?.write(AccessError::WouldBlock)
+    }
+
+    #[track_caller]
+    fn swap(&self, other: &Self) -> Result<(), AccessError> {
+        self.local()?;
+        other.local()?;
+        if std::sync::Arc::ptr_eq(&self.inner, &other.inner) {
+            return Ok(());
+        }
+        let mut this = self.write()?;
+        let mut that = other.write()?;
+        std::mem::swap(&mut *this, &mut *that);
+        Ok(())
+    }
+
+    fn get_cloned(&self) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        let guard = self.read()?;
+        Ok((*guard).clone())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncShared<T> {
+    /// Creates a new asynchronous shared container.
+    pub fn new(value: T) -> Self {
+        AsyncShared {
+            inner: Arc::new(tokio::sync::RwLock::new(value)),
+            changes: Arc::new(tokio::sync::watch::Sender::new(())),
+        }
+    }
+
+    /// Creates a weak reference to this container.
+    pub fn downgrade(&self) -> WeakAsyncShared<T> {
+        WeakAsyncShared {
+            inner: Arc::downgrade(&self.inner),
+            changes: Arc::downgrade(&self.changes),
+        }
+    }
+
+    fn notice(&self) -> WriteNotice<&tokio::sync::watch::Sender<()>> {
+        WriteNotice(&self.changes)
+    }
+
+    /// Returns the value if this is the only strong handle, or the handle otherwise.
+    ///
+    /// Owned guards count as handles. Snapshot streams end once the value is taken.
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        let AsyncShared { inner, changes } = self;
+        Arc::try_unwrap(inner)
+            .map(tokio::sync::RwLock::into_inner)
+            .map_err(|inner| AsyncShared { inner, changes })
+    }
+
+    /// Returns the value if this is the only strong handle, dropping the handle
+    /// otherwise.
+    ///
+    /// Unlike [`try_unwrap`](Self::try_unwrap), when the last handles are dropped
+    /// concurrently exactly one of them gets the value.
+    pub fn into_inner(self) -> Option<T> {
+        Arc::into_inner(self.inner).map(tokio::sync::RwLock::into_inner)
+    }
+
+    /// Moves the value into a [`Shared`] if this is the only strong handle, or
+    /// returns the handle otherwise.
+    ///
+    /// The value is moved, not cloned.
+    pub fn into_sync(self) -> Result<Shared<T>, Self> {
+        self.try_unwrap().map(Shared::new)
+    }
+
+    /// Returns a mutable reference to the value without locking.
+    ///
+    /// Returns `AccessError::NotUnique` while other handles, weak references or
+    /// snapshot streams exist.
+    pub fn get_mut(&mut self) -> Result<&mut T, AccessError> {
+        Arc::get_mut(&mut self.inner)
+            .map(tokio::sync::RwLock::get_mut)
+            .ok_or(AccessError::NotUnique)
+    }
+
+    /// Returns a mutable reference to the value, first moving this handle to a
+    /// clone of the value if other handles or weak references exist.
+    ///
+    /// The clone is taken without waiting, so this returns
+    /// `AccessError::WouldBlock` while another handle writes. Snapshot streams
+    /// stay with the old container.
+    pub fn make_mut(&mut self) -> Result<&mut T, AccessError>
+    where
+        T: Clone,
+    {
+        if Arc::get_mut(&mut self.inner).is_none() {
+            let value = self
+                .inner
+                .try_read()
+                .map_err(|_| AccessError::WouldBlock)?
+                .clone();
+            *self = AsyncShared::new(value);
+        }
+        self.get_mut()
+    }
+
+    /// Acquires a read lock, blocking the current thread until it is available.
+    ///
+    /// This is meant for synchronous code such as drop handlers, FFI callbacks or
+    /// program startup. With the `tokio-rt` feature it returns
+    /// `AccessError::BlockingInRuntime` instead of blocking when called from a thread
+    /// that has entered a tokio runtime, including `spawn_blocking` threads; use
+    /// [`read_async`](AsyncAccess::read_async) there. Other executors are not
+    /// detected, and without the feature the call blocks the executor thread.
+    /// On wasm32 the thread cannot block, so it returns `AccessError::WouldBlock`
+    /// if the lock is held.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::AsyncShared;
+    ///
+    /// let config = AsyncShared::new(String::from("prod"));
+    /// assert_eq!(*config.blocking_read().unwrap(), "prod");
+    /// ```
+    pub fn blocking_read(&self) -> Result<AsyncReadGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            check_blocking_allowed()?;
+            Ok(AsyncReadGuard::from_tokio(self.inner.blocking_read()))
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.inner
+                .try_read()
+                .map(AsyncReadGuard::from_tokio)
+                .map_err(|_| AccessError::WouldBlock)
+        }
+    }
+
+    /// Acquires a write lock, blocking the current thread until it is available.
+    ///
+    /// Fails like [`blocking_read`](Self::blocking_read) when called from inside a
+    /// runtime. Snapshot streams see the write once the guard is dropped.
+    pub fn blocking_write(&self) -> Result<AsyncWriteGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            check_blocking_allowed()?;
+            Ok(AsyncWriteGuard::from_tokio(
+                self.inner.blocking_write(),
+                self.notice(),
+            ))
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.inner
+                .try_write()
+                .map(|guard| AsyncWriteGuard::from_tokio(guard, self.notice()))
+                .map_err(|_| AccessError::WouldBlock)
+        }
+    }
+
+    /// Gets a clone of the contained value, blocking the current thread until the
+    /// read lock is available.
+    ///
+    /// Fails like [`blocking_read`](Self::blocking_read) when called from inside a
+    /// runtime.
+    pub fn blocking_get_cloned(&self) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        let guard = self.blocking_read()?;
+        Ok((*guard).clone())
+    }
+
+    fn owned_notice(&self) -> WriteNotice<Arc<tokio::sync::watch::Sender<()>>> {
+        WriteNotice(Arc::clone(&self.changes))
+    }
+
+    /// Asynchronously acquires a read lock that keeps this container alive.
+    ///
+    /// The returned guard holds a strong reference instead of borrowing `self`, so it
+    /// can be moved into a spawned task.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use shared_container::AsyncShared;
+    ///
+    /// let container = AsyncShared::new(vec![1, 2, 3]);
+    /// let guard = container.read_owned_async().await;
+    /// let len = tokio::spawn(async move { guard.len() }).await.unwrap();
+    /// assert_eq!(len, 3);
+    /// # }
+    /// ```
+    pub async fn read_owned_async(&self) -> OwnedAsyncReadGuard<T> {
+        OwnedAsyncReadGuard(Arc::clone(&self.inner).read_owned().await)
+    }
+
+    /// Asynchronously acquires a write lock that keeps this container alive.
+    ///
+    /// The returned guard holds a strong reference instead of borrowing `self`, so it
+    /// can be moved into a spawned task.
+    pub async fn write_owned_async(&self) -> OwnedAsyncWriteGuard<T> {
+        OwnedAsyncWriteGuard(
+            Arc::clone(&self.inner).write_owned().await,
+            self.owned_notice(),
+        )
+    }
+
+    /// Attempts to acquire an owned read lock without waiting.
+    ///
+    /// Returns `AccessError::WouldBlock` if a writer holds the lock.
+    pub fn try_read_owned_async(&self) -> Result<OwnedAsyncReadGuard<T>, AccessError> {
+        Arc::clone(&self.inner)
+            .try_read_owned()
+            .map(OwnedAsyncReadGuard)
+            .map_err(|_| AccessError::WouldBlock)
+    }
+
+    /// Attempts to acquire an owned write lock without waiting.
+    ///
+    /// Returns `AccessError::WouldBlock` if the lock is held.
+    pub fn try_write_owned_async(&self) -> Result<OwnedAsyncWriteGuard<T>, AccessError> {
+        Arc::clone(&self.inner)
+            .try_write_owned()
+            .map(|guard| OwnedAsyncWriteGuard(guard, self.owned_notice()))
+            .map_err(|_| AccessError::WouldBlock)
+    }
+
+    /// Asynchronously acquires a read lock, giving up after `timeout`.
+    ///
+    /// Returns `AccessError::Timeout` if the lock could not be acquired in time.
+    /// Must be called from within a tokio runtime with the time driver enabled.
+    pub async fn read_timeout_async(
+        &self,
+        timeout: Duration,
+    ) -> Result<AsyncReadGuard<'_, T>, AccessError> {
+        tokio::time::timeout(timeout, self.inner.read())
+            .await
+            .map(AsyncReadGuard::from_tokio)
+            .map_err(|_| AccessError::Timeout)
+    }
+
+    /// Asynchronously acquires a write lock, giving up after `timeout`.
+    ///
+    /// Returns `AccessError::Timeout` if the lock could not be acquired in time.
+    /// Must be called from within a tokio runtime with the time driver enabled.
+    pub async fn write_timeout_async(
+        &self,
+        timeout: Duration,
+    ) -> Result<AsyncWriteGuard<'_, T>, AccessError> {
+        tokio::time::timeout(timeout, self.inner.write())
+            .await
+            .map(|guard| AsyncWriteGuard::from_tokio(guard, self.notice()))
+            .map_err(|_| AccessError::Timeout)
+    }
+
+    /// Asynchronously acquires a read lock, giving up once `deadline` has passed.
+    ///
+    /// Returns `AccessError::Timeout` if the lock could not be acquired in time.
+    /// Must be called from within a tokio runtime with the time driver enabled.
+    pub async fn read_until_async(
+        &self,
+        deadline: Instant,
+    ) -> Result<AsyncReadGuard<'_, T>, AccessError> {
+        tokio::time::timeout_at(deadline.into(), self.inner.read())
+            .await
+            .map(AsyncReadGuard::from_tokio)
+            .map_err(|_| AccessError::Timeout)
+    }
+
+    /// Asynchronously acquires a write lock, giving up once `deadline` has passed.
+    ///
+    /// Returns `AccessError::Timeout` if the lock could not be acquired in time.
+    /// Must be called from within a tokio runtime with the time driver enabled.
+    pub async fn write_until_async(
+        &self,
+        deadline: Instant,
+    ) -> Result<AsyncWriteGuard<'_, T>, AccessError> {
+        tokio::time::timeout_at(deadline.into(), self.inner.write())
+            .await
+            .map(|guard| AsyncWriteGuard::from_tokio(guard, self.notice()))
+            .map_err(|_| AccessError::Timeout)
+    }
+
+    /// Waits for the next write to complete.
+    ///
+    /// Resolves when the next write guard is released or downgraded, including one
+    /// that is already held when this is called. Writes released before the call do
+    /// not count, even if the future is polled later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use shared_container::{AsyncAccess, AsyncShared};
+    ///
+    /// let config = AsyncShared::new(1);
+    /// let changed = config.changed();
+    /// config.replace_async(2).await;
+    /// changed.await;
+    /// # }
+    /// ```
+    pub fn changed(&self) -> impl std::future::Future<Output = ()> + Send + '_ {
+        let mut changes = self.changes.subscribe();
+        async move {
+            // `self` keeps the sender alive, so this cannot fail.
+            let _ = changes.changed().await;
+        }
+    }
+
+    /// Waits until `predicate` holds for the current value.
+    ///
+    /// The predicate is checked against the current value first and then again
+    /// after every write. Returns a read guard taken while it held, so the value
+    /// cannot change before the caller looks at it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use shared_container::{AsyncAccess, AsyncShared};
+    ///
+    /// let progress = AsyncShared::new(0);
+    /// let worker = progress.clone();
+    /// tokio::spawn(async move {
+    ///     for _ in 0..10 {
+    ///         worker.with_mut_async(|p| *p += 10).await;
+    ///     }
+    /// });
+    ///
+    /// let done = progress.wait_until(|p| *p == 100).await;
+    /// assert_eq!(*done, 100);
+    /// # }
+    /// ```
+    pub async fn wait_until<F>(&self, mut predicate: F) -> AsyncReadGuard<'_, T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // Subscribing first means a write landing after the check below still
+        // wakes us up.
+        let mut changes = self.changes.subscribe();
+        loop {
+            {
+                let guard = self.inner.read().await;
+                if predicate(&guard) {
+                    return AsyncReadGuard::from_tokio(guard);
+                }
+            }
+            let _ = changes.changed().await;
+        }
+    }
+
+    /// Returns a stream of snapshots of the value.
+    ///
+    /// The stream yields a clone of the current value, then a fresh clone after
+    /// writes. Writes that complete while the consumer is busy are coalesced, so
+    /// the stream may skip intermediate values but always catches up to the latest
+    /// one. It ends once every strong handle to the container has been dropped.
+    ///
+    /// Only available with the `stream` feature flag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// use futures_util::StreamExt;
+    /// use shared_container::{AsyncAccess, AsyncShared};
+    ///
+    /// let config = AsyncShared::new(String::from("v1"));
+    /// let mut snapshots = config.snapshots();
+    /// assert_eq!(snapshots.next().await.as_deref(), Some("v1"));
+    ///
+    /// config.replace_async(String::from("v2")).await;
+    /// assert_eq!(snapshots.next().await.as_deref(), Some("v2"));
+    ///
+    /// drop(config);
+    /// assert_eq!(snapshots.next().await, None);
+    /// # }
+    /// ```
+    #[cfg(feature = "stream")]
+    pub fn snapshots(&self) -> Snapshots<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let source = SnapshotSource {
+            lock: Arc::downgrade(&self.inner),
+            changes: self.changes.subscribe(),
+        };
+        Snapshots {
+            next: Some(Box::pin(source.next(false))),
+        }
+    }
+}
+
+/// Stream of snapshots of an [`AsyncShared`] container's value.
+///
+/// Created by [`AsyncShared::snapshots`]. Only available with the `stream` feature flag.
+#[cfg(feature = "stream")]
+#[must_use = "streams do nothing unless polled"]
+pub struct Snapshots<T> {
+    /// Produces the next item; `None` once the stream has ended.
+    next: Option<SnapshotFuture<T>>,
+}
+
+#[cfg(feature = "stream")]
+type SnapshotFuture<T> =
+    std::pin::Pin<Box<dyn std::future::Future<Output = Option<(T, SnapshotSource<T>)>> + Send>>;
+
+#[cfg(feature = "stream")]
+struct SnapshotSource<T> {
+    lock: Weak<tokio::sync::RwLock<T>>,
+    changes: tokio::sync::watch::Receiver<()>,
+}
+
+#[cfg(feature = "stream")]
+impl<T: Clone + Send + Sync + 'static> SnapshotSource<T> {
+    async fn next(mut self, wait: bool) -> Option<(T, Self)> {
+        if wait {
+            // Fails once every sender, and so every strong handle, is gone.
+            self.changes.changed().await.ok()?;
+        }
+        let lock = self.lock.upgrade()?;
+        let value = lock.read().await.clone();
+        Some((value, self))
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<T: Clone + Send + Sync + 'static> futures_core::Stream for Snapshots<T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<T>> {
+        use std::task::Poll;
+
+        let Some(next) = self.next.as_mut() else {
+            return Poll::Ready(None);
+        };
+        match next.as_mut().poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Some((value, source))) => {
+                self.next = Some(Box::pin(source.next(true)));
+                Poll::Ready(Some(value))
+            }
+            Poll::Ready(None) => {
+                self.next = None;
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<T> std::fmt::Debug for Snapshots<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Snapshots")
+            .field("ended", &self.next.is_none())
+            .finish()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Clone for AsyncShared<T> {
+    fn clone(&self) -> Self {
+        AsyncShared {
+            inner: Arc::clone(&self.inner),
+            changes: Arc::clone(&self.changes),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncShared<T> {
+    /// Reads the value for `Debug` without waiting.
+    fn debug_view(&self) -> DebugContainer<tokio::sync::RwLockReadGuard<'_, T>> {
+        DebugContainer {
+            name: "AsyncShared",
+            value: self.inner.try_read().map_err(|_| "busy"),
+            strong: self.strong_count(),
+            weak: self.weak_count(),
+        }
+    }
+}
+
+/// Tokio's lock is fair, so a waiting writer makes it busy too.
+#[cfg(feature = "async")]
+impl<T: std::fmt::Debug> std::fmt::Debug for AsyncShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.debug_view().fmt(f)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> WeakAsyncShared<T> {
+    /// Attempts to upgrade the weak reference to a strong reference.
+    pub fn upgrade(&self) -> Option<AsyncShared<T>> {
+        // Both halves are dropped together, but the last strong handle may go away
+        // between the two upgrades.
+        let inner = self.inner.upgrade()?;
+        let changes = self.changes.upgrade()?;
+        Some(AsyncShared { inner, changes })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Clone for WeakAsyncShared<T> {
+    fn clone(&self) -> Self {
+        WeakAsyncShared {
+            inner: self.inner.clone(),
+            changes: self.changes.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncSharedMutex<T> {
+    /// Creates a new asynchronous mutex-backed container.
+    pub fn new(value: T) -> Self {
+        AsyncSharedMutex {
+            inner: Arc::new(tokio::sync::Mutex::new(value)),
+        }
+    }
+
+    /// Creates a weak reference to this container.
+    pub fn downgrade(&self) -> WeakAsyncSharedMutex<T> {
+        WeakAsyncSharedMutex {
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
+
+    /// Returns the value if this is the only strong handle, or the handle otherwise.
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        Arc::try_unwrap(self.inner)
+            .map(tokio::sync::Mutex::into_inner)
+            .map_err(|inner| AsyncSharedMutex { inner })
+    }
+
+    /// Returns the value if this is the only strong handle, dropping the handle
+    /// otherwise.
+    pub fn into_inner(self) -> Option<T> {
+        Arc::into_inner(self.inner).map(tokio::sync::Mutex::into_inner)
+    }
+
+    /// Returns a mutable reference to the value without locking.
+    ///
+    /// Returns `AccessError::NotUnique` while other handles or weak references
+    /// exist.
+    pub fn get_mut(&mut self) -> Result<&mut T, AccessError> {
+        Arc::get_mut(&mut self.inner)
+            .map(tokio::sync::Mutex::get_mut)
+            .ok_or(AccessError::NotUnique)
+    }
+
+    /// Returns a mutable reference to the value, first moving this handle to a
+    /// clone of the value if other handles or weak references exist.
+    ///
+    /// The clone is taken without waiting, so this returns
+    /// `AccessError::WouldBlock` while the lock is held.
+    pub fn make_mut(&mut self) -> Result<&mut T, AccessError>
+    where
+        T: Clone,
+    {
+        if Arc::get_mut(&mut self.inner).is_none() {
+            let value = (*self.try_lock()?).clone();
+            *self = AsyncSharedMutex::new(value);
+        }
+        self.get_mut()
+    }
+
+    /// Acquires the lock, waiting until it is available.
+    pub async fn lock(&self) -> AsyncMutexGuard<'_, T> {
+        AsyncMutexGuard(self.inner.lock().await)
+    }
+
+    /// Acquires the lock if that is possible without waiting.
+    ///
+    /// Returns `AccessError::WouldBlock` if the lock is held.
+    pub fn try_lock(&self) -> Result<AsyncMutexGuard<'_, T>, AccessError> {
+        self.inner
+            .try_lock()
+            .map(AsyncMutexGuard)
+            .map_err(|_| AccessError::WouldBlock)
+    }
+
+    /// Acquires the lock, blocking the current thread until it is available.
+    ///
+    /// Fails like [`AsyncShared::blocking_read`] when called from inside a runtime,
+    /// and on wasm32 returns `AccessError::WouldBlock` if the lock is held.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::AsyncSharedMutex;
+    ///
+    /// let queue = AsyncSharedMutex::new(vec![1]);
+    /// queue.blocking_lock().unwrap().push(2);
+    /// assert_eq!(*queue.blocking_lock().unwrap(), [1, 2]);
+    /// ```
+    pub fn blocking_lock(&self) -> Result<AsyncMutexGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            check_blocking_allowed()?;
+            Ok(AsyncMutexGuard(self.inner.blocking_lock()))
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.try_lock()
+        }
+    }
+
+    /// Runs `f` with exclusive access to the value and returns its result.
+    ///
+    /// The lock is held only for the duration of the call.
+    pub async fn with_lock<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let mut guard = self.lock().await;
+        f(&mut guard)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Clone for AsyncSharedMutex<T> {
+    fn clone(&self) -> Self {
+        AsyncSharedMutex {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncSharedMutex<T> {
+    /// Locks the value for `Debug` without waiting.
+    fn debug_view(&self) -> DebugContainer<tokio::sync::MutexGuard<'_, T>> {
+        DebugContainer {
+            name: "AsyncSharedMutex",
+            value: self.inner.try_lock().map_err(|_| "busy"),
+            strong: self.strong_count(),
+            weak: self.weak_count(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: std::fmt::Debug> std::fmt::Debug for AsyncSharedMutex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.debug_view().fmt(f)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> WeakAsyncSharedMutex<T> {
+    /// Attempts to upgrade the weak reference to a strong reference.
+    pub fn upgrade(&self) -> Option<AsyncSharedMutex<T>> {
+        self.inner.upgrade().map(|inner| AsyncSharedMutex { inner })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Clone for WeakAsyncSharedMutex<T> {
+    fn clone(&self) -> Self {
+        WeakAsyncSharedMutex {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+// ============================================================================
+// AsyncAccess implementation for AsyncShared<T>
+// ============================================================================
+
+#[cfg(feature = "async")]
+impl<T: Send + Sync> AsyncAccess<T> for AsyncShared<T> {
+    async fn read_async<'a>(&'a self) -> AsyncReadGuard<'a, T>
+    where
+        T: 'a,
+    {
+        AsyncReadGuard::from_tokio(self.inner.read().await)
+    }
+
+    async fn write_async<'a>(&'a self) -> AsyncWriteGuard<'a, T>
+    where
+        T: 'a,
+    {
+        AsyncWriteGuard::from_tokio(self.inner.write().await, self.notice())
+    }
+
+    async fn get_cloned_async(&self) -> T
+    where
+        T: Clone,
+    {
+        let guard = self.inner.read().await;
+        (*guard).clone()
+    }
+
+    fn try_read_async(&self) -> Result<AsyncReadGuard<'_, T>, AccessError> {
+        self.inner
+            .try_read()
+            .map(AsyncReadGuard::from_tokio)
+            .map_err(|_| AccessError::WouldBlock)
+    }
+
+    fn try_write_async(&self) -> Result<AsyncWriteGuard<'_, T>, AccessError> {
+        self.inner
+            .try_write()
+            .map(|guard| AsyncWriteGuard::from_tokio(guard, self.notice()))
+            .map_err(|_| AccessError::WouldBlock)
+    }
+
+    async fn swap_async<'a>(&'a self, other: &'a Self)
+    where
+        T: 'a,
+    {
+        if Arc::ptr_eq(&self.inner, &other.inner) {
+            return;
+        }
+
+        // Lock in address order so concurrent swaps of the same pair cannot deadlock.
+        let (first, second) = if Arc::as_ptr(&self.inner) < Arc::as_ptr(&other.inner) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut first = first.write_async().await;
+        let mut second = second.write_async().await;
+        std::mem::swap(&mut *first, &mut *second);
+    }
+}
+
+// ============================================================================
+// AsyncAccess implementation for Shared<T, B>
+// ============================================================================
+
+/// Waiting tasks are woken whenever the lock is released, so an executor thread
+/// never blocks on it. A task waiting in `write_async` holds back new readers on
+/// backends that support it, as [`DefaultBackend`] does.
+///
+/// # Poisoning
+///
+/// The futures cannot report errors, so `read_async`, `write_async` and the methods
+/// built on them ignore poisoning and hand out the value as the panicking writer left
+/// it, whatever the [`PoisonPolicy`]. Use `try_read_async`/`try_write_async`, which
+/// report [`AccessError::Poisoned`], or check [`Shared::is_poisoned`] first where that
+/// matters.
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+impl<T: Send + Sync, B: LockBackend> AsyncAccess<T> for Shared<T, B> {
+    async fn read_async<'a>(&'a self) -> AsyncReadGuard<'a, T>
+    where
+        T: 'a,
+    {
+        self.inner.lock.acquire(B::try_lock_shared).await;
+        // SAFETY: the shared lock was just acquired.
+        let guard = unsafe { SyncReadGuard::new(&*self.inner, self.inner.data.get()) };
+        AsyncReadGuard(AsyncReadLock::Shared(guard))
+    }
+
+    async fn write_async<'a>(&'a self) -> AsyncWriteGuard<'a, T>
+    where
+        T: 'a,
+    {
+        self.inner.lock.acquire_exclusive().await;
+        // SAFETY: the exclusive lock was just acquired.
+        let guard = unsafe { SyncWriteGuard::new(&*self.inner, self.inner.data.get()) };
+        AsyncWriteGuard(AsyncWriteLock::Shared(guard))
+    }
+
+    async fn get_cloned_async(&self) -> T
+    where
+        T: Clone,
+    {
+        let guard = self.read_async().await;
+        (*guard).clone()
+    }
+
+    fn try_read_async(&self) -> Result<AsyncReadGuard<'_, T>, AccessError> {
+        self.try_read()
+            .map(|guard| AsyncReadGuard(AsyncReadLock::Shared(guard)))
+    }
+
+    fn try_write_async(&self) -> Result<AsyncWriteGuard<'_, T>, AccessError> {
+        self.try_write()
+            .map(|guard| AsyncWriteGuard(AsyncWriteLock::Shared(guard)))
+    }
+
+    async fn swap_async<'a>(&'a self, other: &'a Self)
+    where
+        T: 'a,
+    {
+        let this: *const SharedInner<T, B> = &*self.inner;
+        let that: *const SharedInner<T, B> = &*other.inner;
+        if std::ptr::eq(this, that) {
+            return;
+        }
+
+        // Lock in address order so concurrent swaps of the same pair cannot deadlock.
+        let (first, second) = if this < that {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut first = first.write_async().await;
+        let mut second = second.write_async().await;
+        std::mem::swap(&mut *first, &mut *second);
+    }
+}
+
+// ============================================================================
+// LocalAsyncShared
+// ============================================================================
+
+impl<T> LocalAsyncShared<T> {
+    /// Creates a new single-threaded asynchronous container.
+    pub fn new(value: T) -> Self {
+        LocalAsyncShared {
+            inner: std::rc::Rc::new(LocalAsyncInner {
+                lock: lock::LocalAsyncLock::default(),
+                data: UnsafeCell::new(value),
+            }),
+        }
+    }
+
+    /// Creates a weak reference to this container.
+    pub fn downgrade(&self) -> WeakLocalAsyncShared<T> {
+        WeakLocalAsyncShared {
+            inner: std::rc::Rc::downgrade(&self.inner),
+        }
+    }
+}
+
+impl<T> Clone for LocalAsyncShared<T> {
+    fn clone(&self) -> Self {
+        LocalAsyncShared {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> LocalAsyncShared<T> {
+    /// Borrows the value for `Debug` without waiting.
+    fn debug_value(&self) -> Result<LocalAsyncReadGuard<'_, T>, &'static str> {
+        self.try_read_async().map_err(|_| "busy")
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for LocalAsyncShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        DebugContainer {
+            name: "LocalAsyncShared",
+            value: self.debug_value(),
+            strong: self.strong_count(),
+            weak: self.weak_count(),
+        }
+        .fmt(f)
+    }
+}
+
+impl<T> WeakLocalAsyncShared<T> {
+    /// Attempts to upgrade the weak reference to a strong reference.
+    pub fn upgrade(&self) -> Option<LocalAsyncShared<T>> {
+        self.inner.upgrade().map(|inner| LocalAsyncShared { inner })
+    }
+}
+
+/// Shows the value of the container while it is alive, and `<dropped>` afterwards.
+impl<T: std::fmt::Debug> std::fmt::Debug for WeakLocalAsyncShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (strong, weak) = (self.strong_count(), self.weak_count());
+        let shared = self.upgrade();
+        DebugContainer {
+            name: "WeakLocalAsyncShared",
+            value: shared
+                .as_ref()
+                .ok_or("dropped")
+                .and_then(LocalAsyncShared::debug_value),
+            strong,
+            weak,
+        }
+        .fmt(f)
+    }
+}
+
+impl<T> Clone for WeakLocalAsyncShared<T> {
+    fn clone(&self) -> Self {
+        WeakLocalAsyncShared {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> LocalAsyncAccess<T> for LocalAsyncShared<T> {
+    async fn read_async<'a>(&'a self) -> LocalAsyncReadGuard<'a, T>
+    where
+        T: 'a,
+    {
+        self.inner.lock.lock_shared().await;
+        self.inner.read_guard()
+    }
+
+    async fn write_async<'a>(&'a self) -> LocalAsyncWriteGuard<'a, T>
+    where
+        T: 'a,
+    {
+        self.inner.lock.lock_exclusive().await;
+        self.inner.write_guard()
+    }
+
+    fn try_read_async(&self) -> Result<LocalAsyncReadGuard<'_, T>, AccessError> {
+        if self.inner.lock.try_lock_shared() {
+            Ok(self.inner.read_guard())
+        } else {
+            Err(AccessError::WouldBlock)
+        }
+    }
+
+    fn try_write_async(&self) -> Result<LocalAsyncWriteGuard<'_, T>, AccessError> {
+        if self.inner.lock.try_lock_exclusive() {
+            Ok(self.inner.write_guard())
+        } else {
+            Err(AccessError::WouldBlock)
+        }
+    }
+
+    async fn swap_async<'a>(&'a self, other: &'a Self)
+    where
+        T: 'a,
+    {
+        if std::rc::Rc::ptr_eq(&self.inner, &other.inner) {
+            return;
+        }
+
+        // Lock in address order so concurrent swaps of the same pair cannot deadlock.
+        let (first, second) =
+            if std::rc::Rc::as_ptr(&self.inner) < std::rc::Rc::as_ptr(&other.inner) {
+                (self, other)
+            } else {
+                (other, self)
+            };
+        let mut first = first.write_async().await;
+        let mut second = second.write_async().await;
+        std::mem::swap(&mut *first, &mut *second);
+    }
+}
+
+// ============================================================================
+// Conversions for SharedAny
+// ============================================================================
+
+// Conversions for SharedAny
+impl<T> From<Shared<T>> for SharedAny<T> {
+    fn from(shared: Shared<T>) -> Self {
+        SharedAny::Sync(shared)
+    }
+}
+
+impl<T> From<SharedMutex<T>> for SharedAny<T> {
+    fn from(shared: SharedMutex<T>) -> Self {
+        SharedAny::Mutex(shared)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> From<AsyncShared<T>> for SharedAny<T> {
+    fn from(shared: AsyncShared<T>) -> Self {
+        SharedAny::Async(shared)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> From<AsyncSharedMutex<T>> for SharedAny<T> {
+    fn from(shared: AsyncSharedMutex<T>) -> Self {
+        SharedAny::AsyncMutex(shared)
+    }
+}
+
+impl<T> Clone for SharedAny<T> {
+    fn clone(&self) -> Self {
+        match self {
+            SharedAny::Sync(s) => SharedAny::Sync(s.clone()),
+            SharedAny::Mutex(m) => SharedAny::Mutex(m.clone()),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => SharedAny::Async(a.clone()),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => SharedAny::AsyncMutex(m.clone()),
+        }
+    }
+}
+
+/// Shows the mode and, like the `Debug` output of [`Shared`], the value if it can be
+/// read without blocking, or otherwise the lock state with the handle counts.
+impl<T: std::fmt::Debug> std::fmt::Debug for SharedAny<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SharedAny::Sync(s) => f.debug_tuple("Sync").field(s).finish(),
+            SharedAny::Mutex(m) => f.debug_tuple("Mutex").field(m).finish(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => f.debug_tuple("Async").field(a).finish(),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => f.debug_tuple("AsyncMutex").field(m).finish(),
+        }
+    }
+}
+
+impl<T> SharedAny<T> {
+    /// Creates a weak reference to this container.
+    pub fn downgrade(&self) -> WeakSharedAny<T> {
+        match self {
+            SharedAny::Sync(s) => WeakSharedAny::Sync(s.downgrade()),
+            SharedAny::Mutex(m) => WeakSharedAny::Mutex(m.downgrade()),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => WeakSharedAny::Async(a.downgrade()),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => WeakSharedAny::AsyncMutex(m.downgrade()),
+        }
+    }
+
+    /// Returns the value if this is the only strong handle, or the handle otherwise.
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        match self {
+            SharedAny::Sync(s) => s.try_unwrap().map_err(SharedAny::Sync),
+            SharedAny::Mutex(m) => m.try_unwrap().map_err(SharedAny::Mutex),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a.try_unwrap().map_err(SharedAny::Async),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m.try_unwrap().map_err(SharedAny::AsyncMutex),
+        }
+    }
+
+    /// Returns the value if this is the only strong handle, dropping the handle
+    /// otherwise.
+    ///
+    /// A poisoned sync container returns its value inside a [`PoisonError`], as
+    /// [`Shared::into_inner`] does.
+    pub fn into_inner(self) -> Result<Option<T>, PoisonError<T>> {
+        match self {
+            SharedAny::Sync(s) => s.into_inner(),
+            SharedAny::Mutex(m) => m.into_inner(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => Ok(a.into_inner()),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => Ok(m.into_inner()),
+        }
+    }
+
+    /// Moves the value of an async container into the matching sync one.
+    ///
+    /// `Async` becomes `Sync` and `AsyncMutex` becomes `Mutex`. Sync containers are
+    /// returned unchanged, and so is a container with other strong handles, as the
+    /// error.
+    #[cfg(feature = "async")]
+    pub fn try_into_sync(self) -> Result<Self, Self> {
+        match self {
+            SharedAny::Async(a) => a
+                .try_unwrap()
+                .map(|value| SharedAny::Sync(Shared::new(value)))
+                .map_err(SharedAny::Async),
+            SharedAny::AsyncMutex(m) => m
+                .try_unwrap()
+                .map(|value| SharedAny::Mutex(SharedMutex::new(value)))
+                .map_err(SharedAny::AsyncMutex),
+            SharedAny::Sync(_) | SharedAny::Mutex(_) => Ok(self),
+        }
+    }
+
+    /// Moves the value of a sync container into the matching async one.
+    ///
+    /// `Sync` becomes `Async`, and `Mutex` becomes `AsyncMutex`. Async
+    /// containers are returned unchanged, and so is a container with other strong
+    /// handles, as the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::{Shared, SharedAny, SyncAccess};
+    ///
+    /// let state: SharedAny<Vec<u32>> = Shared::new(Vec::new()).into();
+    /// state.write().unwrap().push(1);
+    ///
+    /// let state = state.try_into_async().unwrap();
+    /// assert!(matches!(state, SharedAny::Async(_)));
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn try_into_async(self) -> Result<Self, Self> {
+        match self {
+            SharedAny::Sync(s) => s
+                .into_async()
+                .map(SharedAny::Async)
+                .map_err(SharedAny::Sync),
+            SharedAny::Mutex(m) => m
+                .try_unwrap()
+                .map(|value| SharedAny::AsyncMutex(AsyncSharedMutex::new(value)))
+                .map_err(SharedAny::Mutex),
+            SharedAny::Async(_) | SharedAny::AsyncMutex(_) => Ok(self),
+        }
+    }
+}
+
+impl<T> WeakSharedAny<T> {
+    /// Attempts to upgrade the weak reference to a strong reference.
+    pub fn upgrade(&self) -> Option<SharedAny<T>> {
+        match self {
+            WeakSharedAny::Sync(w) => w.upgrade().map(SharedAny::Sync),
+            WeakSharedAny::Mutex(w) => w.upgrade().map(SharedAny::Mutex),
+            #[cfg(feature = "async")]
+            WeakSharedAny::Async(w) => w.upgrade().map(SharedAny::Async),
+            #[cfg(feature = "async")]
+            WeakSharedAny::AsyncMutex(w) => w.upgrade().map(SharedAny::AsyncMutex),
+        }
+    }
+}
+
+impl<T> Clone for WeakSharedAny<T> {
+    fn clone(&self) -> Self {
+        match self {
+            WeakSharedAny::Sync(w) => WeakSharedAny::Sync(w.clone()),
+            WeakSharedAny::Mutex(w) => WeakSharedAny::Mutex(w.clone()),
+            #[cfg(feature = "async")]
+            WeakSharedAny::Async(w) => WeakSharedAny::Async(w.clone()),
+            #[cfg(feature = "async")]
+            WeakSharedAny::AsyncMutex(w) => WeakSharedAny::AsyncMutex(w.clone()),
+        }
+    }
+}
+
+// ============================================================================
+// Identity
+// ============================================================================
+
+/// Identifies a container by its allocation.
+///
+/// All handles and weak references of one container share the same id, and no
+/// other live container has it. Once the container and all its weak references
+/// are gone, its id may be reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContainerId(usize);
+
+impl ContainerId {
+    fn of<P>(ptr: *const P) -> Self {
+        ContainerId(ptr.cast::<()>() as usize)
+    }
+}
+
+/// Containers and weak references that can report their [`ContainerId`].
+pub trait Identity {
+    /// Returns the identity of the container.
+    fn id(&self) -> ContainerId;
+}
+
+/// Compares, orders and hashes a container by identity instead of by value.
+///
+/// Wrap handles in `ByIdentity` to use them as `HashMap` keys or to deduplicate
+/// them, without locking or requiring anything of `T`. Clippy's `mutable_key_type`
+/// lint does not know that and may need to be allowed for such collections.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use shared_container::{ByIdentity, Shared};
+///
+/// let a = Shared::new(1);
+/// let b = Shared::new(1);
+///
+/// let mut seen = HashSet::new();
+/// assert!(seen.insert(ByIdentity(a.clone())));
+/// assert!(!seen.insert(ByIdentity(a)));
+/// assert!(seen.insert(ByIdentity(b)));
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByIdentity<S>(pub S);
+
+impl<S> Deref for ByIdentity<S> {
+    type Target = S;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S: Identity> PartialEq for ByIdentity<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id() == other.0.id()
+    }
+}
+
+impl<S: Identity> Eq for ByIdentity<S> {}
+
+impl<S: Identity> PartialOrd for ByIdentity<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Identity> Ord for ByIdentity<S> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.id().cmp(&other.0.id())
+    }
+}
+
+impl<S: Identity> std::hash::Hash for ByIdentity<S> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.id().hash(state);
+    }
+}
+
+impl<T, B> Shared<T, B> {
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::Shared;
+    ///
+    /// let a = Shared::new(1);
+    /// assert!(a.ptr_eq(&a.clone()));
+    /// assert!(!a.ptr_eq(&Shared::new(1)));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    ///
+    /// Owned guards hold a handle of their own.
+    pub fn strong_count(&self) -> usize {
+        #[cfg(target_arch = "wasm32")]
+        {
+            std::rc::Rc::strong_count(&self.inner)
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::sync::Arc::strong_count(&self.inner)
+        }
+    }
+
+    /// Returns the number of weak references to the container.
+    ///
+    /// Subscriptions hold a weak reference of their own.
+    pub fn weak_count(&self) -> usize {
+        #[cfg(target_arch = "wasm32")]
+        {
+            std::rc::Rc::weak_count(&self.inner)
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::sync::Arc::weak_count(&self.inner)
+        }
+    }
+
+    /// Returns the identity of the container, shared by all its handles and weak
+    /// references.
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(&*self.inner)
+    }
+}
+
+impl<T, B> WeakShared<T, B> {
+    /// Returns whether both weak references belong to the same container.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container, or 0 once it is gone.
+    pub fn strong_count(&self) -> usize {
+        self.inner.strong_count()
+    }
+
+    /// Returns the number of weak references to the container, or 0 once it is gone.
+    pub fn weak_count(&self) -> usize {
+        self.inner.weak_count()
+    }
+
+    /// Returns the identity of the container, the same as its handles' [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(self.inner.as_ptr())
+    }
+}
+
+impl<T> SharedMutex<T> {
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// See [`Shared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        self.inner.strong_count()
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        self.inner.weak_count()
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        self.inner.id()
+    }
+}
+
+impl<T> WeakSharedMutex<T> {
+    /// Returns whether both weak references belong to the same container.
+    ///
+    /// See [`WeakShared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        self.inner.strong_count()
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        self.inner.weak_count()
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`WeakShared::id`].
+    pub fn id(&self) -> ContainerId {
+        self.inner.id()
+    }
+}
+
+impl<T> LocalShared<T> {
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// See [`Shared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        std::rc::Rc::strong_count(&self.inner)
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        std::rc::Rc::weak_count(&self.inner)
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(&*self.inner)
+    }
+}
+
+impl<T> WeakLocalShared<T> {
+    /// Returns whether both weak references belong to the same container.
+    ///
+    /// See [`WeakShared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        self.inner.strong_count()
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        self.inner.weak_count()
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`WeakShared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(self.inner.as_ptr())
+    }
+}
+
+impl<T> ThreadBoundShared<T> {
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// See [`Shared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        std::sync::Arc::strong_count(&self.inner)
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        std::sync::Arc::weak_count(&self.inner)
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(&*self.inner)
+    }
+}
+
+impl<T> WeakThreadBoundShared<T> {
+    /// Returns whether both weak references belong to the same container.
+    ///
+    /// See [`WeakShared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        self.inner.strong_count()
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        self.inner.weak_count()
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`WeakShared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(self.inner.as_ptr())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncShared<T> {
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// See [`Shared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        Arc::weak_count(&self.inner)
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(&*self.inner)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> WeakAsyncShared<T> {
+    /// Returns whether both weak references belong to the same container.
+    ///
+    /// See [`WeakShared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        self.inner.strong_count()
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        self.inner.weak_count()
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`WeakShared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(self.inner.as_ptr())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncSharedMutex<T> {
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// See [`Shared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        Arc::weak_count(&self.inner)
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(&*self.inner)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> WeakAsyncSharedMutex<T> {
+    /// Returns whether both weak references belong to the same container.
+    ///
+    /// See [`WeakShared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        self.inner.strong_count()
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        self.inner.weak_count()
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`WeakShared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(self.inner.as_ptr())
+    }
+}
+
+impl<T> LocalAsyncShared<T> {
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// See [`Shared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        std::rc::Rc::strong_count(&self.inner)
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        std::rc::Rc::weak_count(&self.inner)
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(&*self.inner)
+    }
+}
+
+impl<T> WeakLocalAsyncShared<T> {
+    /// Returns whether both weak references belong to the same container.
+    ///
+    /// See [`WeakShared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        self.inner.strong_count()
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        self.inner.weak_count()
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`WeakShared::id`].
+    pub fn id(&self) -> ContainerId {
+        ContainerId::of(self.inner.as_ptr())
+    }
+}
+
+impl<T> SharedAny<T> {
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// See [`Shared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        match self {
+            SharedAny::Sync(s) => s.strong_count(),
+            SharedAny::Mutex(m) => m.strong_count(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a.strong_count(),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m.strong_count(),
+        }
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        match self {
+            SharedAny::Sync(s) => s.weak_count(),
+            SharedAny::Mutex(m) => m.weak_count(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a.weak_count(),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m.weak_count(),
+        }
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        match self {
+            SharedAny::Sync(s) => s.id(),
+            SharedAny::Mutex(m) => m.id(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a.id(),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m.id(),
+        }
+    }
+}
+
+impl<T> WeakSharedAny<T> {
+    /// Returns whether both weak references belong to the same container.
+    ///
+    /// See [`WeakShared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        match self {
+            WeakSharedAny::Sync(s) => s.strong_count(),
+            WeakSharedAny::Mutex(m) => m.strong_count(),
+            #[cfg(feature = "async")]
+            WeakSharedAny::Async(a) => a.strong_count(),
+            #[cfg(feature = "async")]
+            WeakSharedAny::AsyncMutex(m) => m.strong_count(),
+        }
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        match self {
+            WeakSharedAny::Sync(s) => s.weak_count(),
+            WeakSharedAny::Mutex(m) => m.weak_count(),
+            #[cfg(feature = "async")]
+            WeakSharedAny::Async(a) => a.weak_count(),
+            #[cfg(feature = "async")]
+            WeakSharedAny::AsyncMutex(m) => m.weak_count(),
+        }
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`WeakShared::id`].
+    pub fn id(&self) -> ContainerId {
+        match self {
+            WeakSharedAny::Sync(s) => s.id(),
+            WeakSharedAny::Mutex(m) => m.id(),
+            #[cfg(feature = "async")]
+            WeakSharedAny::Async(a) => a.id(),
+            #[cfg(feature = "async")]
+            WeakSharedAny::AsyncMutex(m) => m.id(),
+        }
+    }
+}
+
+impl<T, B> Identity for Shared<T, B> {
+    fn id(&self) -> ContainerId {
+        Shared::id(self)
+    }
+}
+
+impl<T, B> Identity for WeakShared<T, B> {
+    fn id(&self) -> ContainerId {
+        WeakShared::id(self)
+    }
+}
+
+impl<T> Identity for SharedMutex<T> {
+    fn id(&self) -> ContainerId {
+        SharedMutex::id(self)
+    }
+}
+
+impl<T> Identity for WeakSharedMutex<T> {
+    fn id(&self) -> ContainerId {
+        WeakSharedMutex::id(self)
+    }
+}
+
+impl<T> Identity for LocalShared<T> {
+    fn id(&self) -> ContainerId {
+        LocalShared::id(self)
+    }
+}
+
+impl<T> Identity for WeakLocalShared<T> {
+    fn id(&self) -> ContainerId {
+        WeakLocalShared::id(self)
+    }
+}
+
+impl<T> Identity for ThreadBoundShared<T> {
+    fn id(&self) -> ContainerId {
+        ThreadBoundShared::id(self)
+    }
+}
+
+impl<T> Identity for WeakThreadBoundShared<T> {
+    fn id(&self) -> ContainerId {
+        WeakThreadBoundShared::id(self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Identity for AsyncShared<T> {
+    fn id(&self) -> ContainerId {
+        AsyncShared::id(self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Identity for WeakAsyncShared<T> {
+    fn id(&self) -> ContainerId {
+        WeakAsyncShared::id(self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Identity for AsyncSharedMutex<T> {
+    fn id(&self) -> ContainerId {
+        AsyncSharedMutex::id(self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Identity for WeakAsyncSharedMutex<T> {
+    fn id(&self) -> ContainerId {
+        WeakAsyncSharedMutex::id(self)
+    }
+}
+
+impl<T> Identity for LocalAsyncShared<T> {
+    fn id(&self) -> ContainerId {
+        LocalAsyncShared::id(self)
+    }
+}
+
+impl<T> Identity for WeakLocalAsyncShared<T> {
+    fn id(&self) -> ContainerId {
+        WeakLocalAsyncShared::id(self)
+    }
+}
+
+impl<T> Identity for SharedAny<T> {
+    fn id(&self) -> ContainerId {
+        SharedAny::id(self)
+    }
+}
+
+impl<T> Identity for WeakSharedAny<T> {
+    fn id(&self) -> ContainerId {
+        WeakSharedAny::id(self)
+    }
+}
+
+// ============================================================================
+// Value-based trait implementations
+// ============================================================================
+
+/// Containers whose value the standard trait implementations can read.
+trait ReadValue<T> {
+    /// Reads the value, or says why it can't be read right now.
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError>;
+}
+
+/// Compares two containers by value, or says why one of the values can't be read.
+///
+/// A container compared with itself is read once: a second read lock could queue
+/// behind a waiting writer, and a mutex can't be locked twice at all. Distinct
+/// containers are read in address order, so two comparisons running in opposite
+/// directions can't deadlock.
+fn compare_values<S, T, R>(
+    a: &S,
+    b: &S,
+    by_value: impl FnOnce(&T, &T) -> R,
+) -> Result<R, AccessError>
+where
+    S: ReadValue<T> + Identity,
+{
+    let (a_id, b_id) = (a.id(), b.id());
+    if a_id == b_id {
+        let value = a.read_value()?;
+        return Ok(by_value(&value, &value));
+    }
+
+    let (first, second) = if a_id < b_id { (a, b) } else { (b, a) };
+    let first = first.read_value()?;
+    let second = second.read_value()?;
+    if a_id < b_id {
+        Ok(by_value(&first, &second))
+    } else {
+        Ok(by_value(&second, &first))
+    }
+}
+
+/// Formats a container's value, or the reason it can't be read.
+fn display_value<S, T>(container: &S, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+where
+    S: ReadValue<T>,
+    T: std::fmt::Display,
+{
+    match container.read_value() {
+        Ok(value) => value.fmt(f),
+        Err(error) => write!(f, "<{error}>"),
+    }
+}
+
+impl<T, B: LockBackend> ReadValue<T> for Shared<T, B> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        self.read()
+    }
+}
+
+impl<T> ReadValue<T> for SharedMutex<T> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        self.lock()
+    }
+}
+
+impl<T> ReadValue<T> for LocalShared<T> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        self.read()
+    }
+}
+
+impl<T> ReadValue<T> for ThreadBoundShared<T> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        self.read()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> AsyncShared<T> {
+    /// Blocks like [`blocking_read`](Self::blocking_read) where the `tokio-rt` feature
+    /// rules out a runtime, and doesn't wait otherwise.
+    fn read_value(&self) -> Result<AsyncReadGuard<'_, T>, AccessError> {
+        if cfg!(feature = "tokio-rt") {
+            match self.blocking_read() {
+                Err(AccessError::BlockingInRuntime) => {}
+                result => return result,
+            }
+        }
+        self.inner
+            .try_read()
+            .map(AsyncReadGuard::from_tokio)
+            .map_err(|_| AccessError::WouldBlock)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> ReadValue<T> for AsyncShared<T> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        AsyncShared::read_value(self)
     }
 }
 
-/// Write guard for asynchronous access.
 #[cfg(feature = "async")]
-#[derive(Debug)]
-pub struct AsyncWriteGuard<'a, T>(tokio::sync::RwLockWriteGuard<'a, T>);
+impl<T> AsyncSharedMutex<T> {
+    /// Blocks until the lock is free where the `tokio-rt` feature rules out a runtime,
+    /// and doesn't wait otherwise.
+    fn read_value(&self) -> Result<AsyncMutexGuard<'_, T>, AccessError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if cfg!(feature = "tokio-rt") && check_blocking_allowed().is_ok() {
+            return Ok(AsyncMutexGuard(self.inner.blocking_lock()));
+        }
+        self.try_lock()
+    }
+}
 
 #[cfg(feature = "async")]
-impl<'a, T> Deref for AsyncWriteGuard<'a, T> {
-    type Target = T;
+impl<T> ReadValue<T> for AsyncSharedMutex<T> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        AsyncSharedMutex::read_value(self)
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
-        self.0.deref()
+impl<T> ReadValue<T> for LocalAsyncShared<T> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        self.try_read_async()
     }
 }
 
-#[cfg(feature = "async")]
-impl<'a, T> DerefMut for AsyncWriteGuard<'a, T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.0.deref_mut()
+impl<T> SharedAny<T> {
+    /// Reads the value of any variant through a sync guard, as `ReadValue` does.
+    fn read_value(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        match self {
+            SharedAny::Sync(s) => s.read(),
+            SharedAny::Mutex(m) => m.read(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a.read_value().map(AsyncReadGuard::into_sync),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m
+                .read_value()
+                .map(|guard| SyncWriteGuard::downgrade(AsyncMutexGuard::into_sync(guard))),
+        }
     }
 }
 
-/// A synchronous shared container that works across platforms.
-///
-/// On wasm32 targets: uses `Rc<RefCell<T>>`
-/// On other targets: uses `Arc<RwLock<T>>`
-#[derive(Debug)]
-pub struct Shared<T> {
-    #[cfg(target_arch = "wasm32")]
-    inner: Rc<RefCell<T>>,
+impl<T> ReadValue<T> for SharedAny<T> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        SharedAny::read_value(self)
+    }
+}
 
-    #[cfg(not(target_arch = "wasm32"))]
-    inner: std::sync::Arc<std::sync::RwLock<T>>,
+impl<T: Default, B: LockBackend> Default for Shared<T, B> {
+    fn default() -> Self {
+        Shared::with_backend(T::default())
+    }
 }
 
-/// A weak reference to a `Shared<T>`.
-#[derive(Debug)]
-pub struct WeakShared<T> {
-    #[cfg(target_arch = "wasm32")]
-    inner: RcWeak<RefCell<T>>,
+impl<T, B: LockBackend> From<T> for Shared<T, B> {
+    fn from(value: T) -> Self {
+        Shared::with_backend(value)
+    }
+}
 
-    #[cfg(not(target_arch = "wasm32"))]
-    inner: std::sync::Weak<std::sync::RwLock<T>>,
+impl<T, B: LockBackend> Shared<T, B> {
+    /// Compares the values like `==`, but reports a value that can't be read instead
+    /// of treating it as unequal.
+    ///
+    /// Values are read the way [`read`](SyncAccess::read) does. Comparing a
+    /// container with itself takes the lock once, and two containers are locked in
+    /// address order, so comparisons can't deadlock each other.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shared_container::Shared;
+    ///
+    /// let a = Shared::new(1);
+    /// assert_eq!(a.try_eq(&Shared::new(1)), Ok(true));
+    /// assert_eq!(a.try_cmp(&Shared::new(2)), Ok(std::cmp::Ordering::Less));
+    /// ```
+    pub fn try_eq(&self, other: &Self) -> Result<bool, AccessError>
+    where
+        T: PartialEq,
+    {
+        compare_values(self, other, T::eq)
+    }
+
+    /// Compares the values like [`PartialOrd::partial_cmp`], but reports a value that
+    /// can't be read instead of returning `None`.
+    pub fn try_partial_cmp(&self, other: &Self) -> Result<Option<std::cmp::Ordering>, AccessError>
+    where
+        T: PartialOrd,
+    {
+        compare_values(self, other, T::partial_cmp)
+    }
+
+    /// Orders the values like [`Ord::cmp`], or reports a value that can't be read.
+    pub fn try_cmp(&self, other: &Self) -> Result<std::cmp::Ordering, AccessError>
+    where
+        T: Ord,
+    {
+        compare_values(self, other, T::cmp)
+    }
+}
+
+/// A value that can't be read is unequal to everything, itself included, the way
+/// `NaN` is. [`Shared::try_eq`] reports the error instead.
+impl<T: PartialEq, B: LockBackend> PartialEq for Shared<T, B> {
+    fn eq(&self, other: &Self) -> bool {
+        compare_values(self, other, T::eq).unwrap_or(false)
+    }
+}
+
+impl<T: PartialOrd, B: LockBackend> PartialOrd for Shared<T, B> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
+
+impl<T: std::fmt::Display, B: LockBackend> std::fmt::Display for Shared<T, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
+    }
+}
+
+/// Gives each container the `try_eq`, `try_partial_cmp` and `try_cmp` methods of
+/// [`Shared`], reading its value through `ReadValue`.
+macro_rules! impl_try_comparisons {
+    ($($(#[$attr:meta])* $container:ident),* $(,)?) => {$(
+        $(#[$attr])*
+        impl<T> $container<T> {
+            /// Compares the values like `==`, but reports a value that can't be read.
+            ///
+            /// See [`Shared::try_eq`].
+            pub fn try_eq(&self, other: &Self) -> Result<bool, AccessError>
+            where
+                T: PartialEq,
+            {
+                compare_values(self, other, T::eq)
+            }
+
+            /// Compares the values like [`PartialOrd::partial_cmp`], but reports a
+            /// value that can't be read.
+            pub fn try_partial_cmp(
+                &self,
+                other: &Self,
+            ) -> Result<Option<std::cmp::Ordering>, AccessError>
+            where
+                T: PartialOrd,
+            {
+                compare_values(self, other, T::partial_cmp)
+            }
+
+            /// Orders the values like [`Ord::cmp`], or reports a value that can't be
+            /// read.
+            pub fn try_cmp(&self, other: &Self) -> Result<std::cmp::Ordering, AccessError>
+            where
+                T: Ord,
+            {
+                compare_values(self, other, T::cmp)
+            }
+        }
+    )*};
+}
+
+impl_try_comparisons!(
+    SharedMutex,
+    LocalShared,
+    ThreadBoundShared,
+    #[cfg(feature = "async")]
+    AsyncShared,
+    #[cfg(feature = "async")]
+    AsyncSharedMutex,
+    LocalAsyncShared,
+    SharedAny,
+    LocalSharedAny,
+);
+
+impl<T: Default> Default for SharedMutex<T> {
+    fn default() -> Self {
+        SharedMutex::new(T::default())
+    }
+}
+
+impl<T> From<T> for SharedMutex<T> {
+    fn from(value: T) -> Self {
+        SharedMutex::new(value)
+    }
+}
+
+impl<T: PartialEq> PartialEq for SharedMutex<T> {
+    fn eq(&self, other: &Self) -> bool {
+        compare_values(self, other, T::eq).unwrap_or(false)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for SharedMutex<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for SharedMutex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
+    }
+}
+
+impl<T: Default> Default for LocalShared<T> {
+    fn default() -> Self {
+        LocalShared::new(T::default())
+    }
+}
+
+impl<T> From<T> for LocalShared<T> {
+    fn from(value: T) -> Self {
+        LocalShared::new(value)
+    }
+}
+
+impl<T: PartialEq> PartialEq for LocalShared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        compare_values(self, other, T::eq).unwrap_or(false)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for LocalShared<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for LocalShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
+    }
+}
+
+impl<T: Default> Default for ThreadBoundShared<T> {
+    fn default() -> Self {
+        ThreadBoundShared::new(T::default())
+    }
+}
+
+impl<T> From<T> for ThreadBoundShared<T> {
+    fn from(value: T) -> Self {
+        ThreadBoundShared::new(value)
+    }
+}
+
+impl<T: PartialEq> PartialEq for ThreadBoundShared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        compare_values(self, other, T::eq).unwrap_or(false)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for ThreadBoundShared<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for ThreadBoundShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
+    }
 }
 
-/// An asynchronous shared container using tokio primitives.
-///
-/// Only available with the `async` feature flag.
 #[cfg(feature = "async")]
-#[derive(Debug)]
-pub struct AsyncShared<T> {
-    inner: Arc<tokio::sync::RwLock<T>>,
+impl<T: Default> Default for AsyncShared<T> {
+    fn default() -> Self {
+        AsyncShared::new(T::default())
+    }
 }
 
 #[cfg(feature = "async")]
-unsafe impl<T: Send> Send for AsyncShared<T> {}
+impl<T> From<T> for AsyncShared<T> {
+    fn from(value: T) -> Self {
+        AsyncShared::new(value)
+    }
+}
+
+#[cfg(feature = "async-cmp")]
+impl<T: PartialEq> PartialEq for AsyncShared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        compare_values(self, other, T::eq).unwrap_or(false)
+    }
+}
+
+#[cfg(feature = "async-cmp")]
+impl<T: PartialOrd> PartialOrd for AsyncShared<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
 
 #[cfg(feature = "async")]
-unsafe impl<T: Send + Sync> Sync for AsyncShared<T> {}
+impl<T: std::fmt::Display> std::fmt::Display for AsyncShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
+    }
+}
 
-/// A weak reference to an `AsyncShared<T>`.
 #[cfg(feature = "async")]
-#[derive(Debug)]
-pub struct WeakAsyncShared<T> {
-    inner: Weak<tokio::sync::RwLock<T>>,
+impl<T: Default> Default for AsyncSharedMutex<T> {
+    fn default() -> Self {
+        AsyncSharedMutex::new(T::default())
+    }
 }
 
-/// A universal container that can hold either sync or async variants.
-///
-/// This enum allows writing generic code that works with both sync and async containers,
-/// but requires explicit handling of the mode mismatch via `Result`.
-#[derive(Debug)]
-pub enum SharedAny<T> {
-    Sync(Shared<T>),
-    #[cfg(feature = "async")]
-    Async(AsyncShared<T>),
+#[cfg(feature = "async")]
+impl<T> From<T> for AsyncSharedMutex<T> {
+    fn from(value: T) -> Self {
+        AsyncSharedMutex::new(value)
+    }
 }
 
-/// A weak reference to a `SharedAny<T>`.
-#[derive(Debug)]
-pub enum WeakSharedAny<T> {
-    Sync(WeakShared<T>),
-    #[cfg(feature = "async")]
-    Async(WeakAsyncShared<T>),
+#[cfg(feature = "async-cmp")]
+impl<T: PartialEq> PartialEq for AsyncSharedMutex<T> {
+    fn eq(&self, other: &Self) -> bool {
+        compare_values(self, other, T::eq).unwrap_or(false)
+    }
 }
 
-// ============================================================================
-// Basic constructors and conversions
-// ============================================================================
+#[cfg(feature = "async-cmp")]
+impl<T: PartialOrd> PartialOrd for AsyncSharedMutex<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
 
-impl<T> Shared<T> {
-    /// Creates a new synchronous shared container.
-    pub fn new(value: T) -> Self {
-        #[cfg(target_arch = "wasm32")]
-        {
-            Shared {
-                inner: Rc::new(RefCell::new(value)),
-            }
-        }
+#[cfg(feature = "async")]
+impl<T: std::fmt::Display> std::fmt::Display for AsyncSharedMutex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
+    }
+}
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            Shared {
-                inner: std::sync::Arc::new(std::sync::RwLock::new(value)),
-            }
-        }
+impl<T: Default> Default for LocalAsyncShared<T> {
+    fn default() -> Self {
+        LocalAsyncShared::new(T::default())
     }
+}
 
-    /// Creates a weak reference to this container.
-    pub fn downgrade(&self) -> WeakShared<T> {
-        #[cfg(target_arch = "wasm32")]
-        {
-            WeakShared {
-                inner: Rc::downgrade(&self.inner),
-            }
-        }
+impl<T> From<T> for LocalAsyncShared<T> {
+    fn from(value: T) -> Self {
+        LocalAsyncShared::new(value)
+    }
+}
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            WeakShared {
-                inner: std::sync::Arc::downgrade(&self.inner),
-            }
-        }
+impl<T: PartialEq> PartialEq for LocalAsyncShared<T> {
+    fn eq(&self, other: &Self) -> bool {
+        compare_values(self, other, T::eq).unwrap_or(false)
     }
 }
 
-impl<T> Clone for Shared<T> {
-    fn clone(&self) -> Self {
-        #[cfg(target_arch = "wasm32")]
-        {
-            Shared {
-                inner: Rc::clone(&self.inner),
-            }
-        }
+impl<T: PartialOrd> PartialOrd for LocalAsyncShared<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            Shared {
-                inner: std::sync::Arc::clone(&self.inner),
-            }
-        }
+impl<T: std::fmt::Display> std::fmt::Display for LocalAsyncShared<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
     }
 }
 
-impl<T> WeakShared<T> {
-    /// Attempts to upgrade the weak reference to a strong reference.
-    pub fn upgrade(&self) -> Option<Shared<T>> {
-        #[cfg(target_arch = "wasm32")]
-        {
-            self.inner.upgrade().map(|inner| Shared { inner })
-        }
+impl<T: Default> Default for SharedAny<T> {
+    fn default() -> Self {
+        SharedAny::Sync(Shared::default())
+    }
+}
 
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.inner.upgrade().map(|inner| Shared { inner })
+impl<T> SharedAny<T> {
+    /// Whether `PartialEq` and `PartialOrd` may read the value. The async variants
+    /// need the `async-cmp` feature, as [`AsyncShared`] does on its own.
+    fn compares_by_value(&self) -> bool {
+        #[cfg(feature = "async")]
+        if matches!(self, SharedAny::Async(_) | SharedAny::AsyncMutex(_)) {
+            return cfg!(feature = "async-cmp");
         }
+        true
     }
 }
 
-impl<T> Clone for WeakShared<T> {
-    fn clone(&self) -> Self {
-        WeakShared {
-            inner: self.inner.clone(),
+/// Without the `async-cmp` feature an async variant is unequal to everything, like a
+/// value that can't be read.
+impl<T: PartialEq> PartialEq for SharedAny<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.compares_by_value()
+            && other.compares_by_value()
+            && compare_values(self, other, T::eq).unwrap_or(false)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for SharedAny<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if !(self.compares_by_value() && other.compares_by_value()) {
+            return None;
         }
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for SharedAny<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
     }
 }
 
 // ============================================================================
-// SyncAccess implementation for Shared<T>
+// Trait implementations for SharedAny<T>
 // ============================================================================
 
-impl<T> SyncAccess<T> for Shared<T> {
+impl<T> SyncAccess<T> for SharedAny<T> {
     fn read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.inner
-                .read()
-                .map(SyncReadGuard::Std)
-                .map_err(|_| AccessError::Poisoned)
+        match self {
+            SharedAny::Sync(s) => s.read(),
+            SharedAny::Mutex(m) => m.read(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a.blocking_read().map(AsyncReadGuard::into_sync),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m
+                .blocking_lock()
+                .map(|guard| SyncWriteGuard::downgrade(AsyncMutexGuard::into_sync(guard))),
+        }
+    }
+
+    #[track_caller]
+    fn write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        match self {
+            SharedAny::Sync(s) => s.write(),
+            SharedAny::Mutex(m) => m.write(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a.blocking_write().map(AsyncWriteGuard::into_sync),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m.blocking_lock().map(AsyncMutexGuard::into_sync),
         }
+    }
 
-        #[cfg(target_arch = "wasm32")]
-        {
-            self.inner
-                .try_borrow()
-                .map(SyncReadGuard::Wasm)
-                .map_err(|_| AccessError::BorrowConflict)
+    fn try_read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
+        match self {
+            SharedAny::Sync(s) => s.try_read(),
+            SharedAny::Mutex(m) => m.try_read(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a
+                .inner
+                .try_read()
+                .map(|guard| AsyncReadGuard::into_sync(AsyncReadGuard::from_tokio(guard)))
+                .map_err(|_| AccessError::WouldBlock),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m
+                .try_lock()
+                .map(|guard| SyncWriteGuard::downgrade(AsyncMutexGuard::into_sync(guard))),
         }
     }
 
-    fn write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            self.inner
-                .write()
-                .map(SyncWriteGuard::Std)
-                .map_err(|_| AccessError::Poisoned)
+    #[track_caller]
+    fn try_write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
+        match self {
+            SharedAny::Sync(s) => s.try_write(),
+            SharedAny::Mutex(m) => m.try_write(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a
+                .inner
+                .try_write()
+                .map(|guard| {
+                    AsyncWriteGuard::into_sync(AsyncWriteGuard::from_tokio(guard, a.notice()))
+                })
+                .map_err(|_| AccessError::WouldBlock),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m.try_lock().map(AsyncMutexGuard::into_sync),
         }
+    }
 
-        #[cfg(target_arch = "wasm32")]
-        {
-            self.inner
-                .try_borrow_mut()
-                .map(SyncWriteGuard::Wasm)
-                .map_err(|_| AccessError::BorrowConflict)
+    #[track_caller]
+    fn swap(&self, other: &Self) -> Result<(), AccessError> {
+        match (self, other) {
+            (SharedAny::Sync(a), SharedAny::Sync(b)) => a.swap(b),
+            (SharedAny::Mutex(a), SharedAny::Mutex(b)) => a.swap(b),
+            _ => Err(AccessError::UnsupportedMode),
         }
     }
 
@@ -1217,219 +7834,526 @@ impl<T> SyncAccess<T> for Shared<T> {
     where
         T: Clone,
     {
-        let guard = self.read()?;
-        Ok((*guard).clone())
+        match self {
+            SharedAny::Sync(s) => s.get_cloned(),
+            SharedAny::Mutex(m) => m.get_cloned(),
+            #[cfg(feature = "async")]
+            SharedAny::Async(a) => a.blocking_get_cloned(),
+            #[cfg(feature = "async")]
+            SharedAny::AsyncMutex(m) => m.blocking_lock().map(|guard| (*guard).clone()),
+        }
     }
 }
 
+/// The container behind a [`SharedAny`], borrowed on its own so that swaps can order
+/// their locks by the address of the value.
 #[cfg(feature = "async")]
-impl<T> AsyncShared<T> {
-    /// Creates a new asynchronous shared container.
-    pub fn new(value: T) -> Self {
-        AsyncShared {
-            inner: Arc::new(tokio::sync::RwLock::new(value)),
+enum AsyncTarget<'a, T> {
+    Async(&'a AsyncShared<T>),
+    AsyncMutex(&'a AsyncSharedMutex<T>),
+    Sync(&'a Shared<T>),
+    Mutex(&'a Shared<T, lock::MutexBackend>),
+}
+
+#[cfg(feature = "async")]
+impl<T> Clone for AsyncTarget<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Copy for AsyncTarget<'_, T> {}
+
+#[cfg(feature = "async")]
+impl<'a, T: Send + Sync> AsyncTarget<'a, T> {
+    async fn read(self) -> AsyncReadGuard<'a, T> {
+        match self {
+            AsyncTarget::Async(a) => a.read_async().await,
+            AsyncTarget::AsyncMutex(m) => {
+                let guard = AsyncMutexGuard::into_sync(m.lock().await);
+                AsyncReadGuard(AsyncReadLock::Shared(SyncWriteGuard::downgrade(guard)))
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            AsyncTarget::Sync(s) => s.read_async().await,
+            #[cfg(not(target_arch = "wasm32"))]
+            AsyncTarget::Mutex(s) => s.read_async().await,
+            #[cfg(target_arch = "wasm32")]
+            AsyncTarget::Sync(_) | AsyncTarget::Mutex(_) => retry(|| self.try_read()).await,
         }
     }
 
-    /// Creates a weak reference to this container.
-    pub fn downgrade(&self) -> WeakAsyncShared<T> {
-        WeakAsyncShared {
-            inner: Arc::downgrade(&self.inner),
+    async fn write(self) -> AsyncWriteGuard<'a, T> {
+        match self {
+            AsyncTarget::Async(a) => a.write_async().await,
+            AsyncTarget::AsyncMutex(m) => AsyncWriteGuard(AsyncWriteLock::Shared(
+                AsyncMutexGuard::into_sync(m.lock().await),
+            )),
+            #[cfg(not(target_arch = "wasm32"))]
+            AsyncTarget::Sync(s) => s.write_async().await,
+            #[cfg(not(target_arch = "wasm32"))]
+            AsyncTarget::Mutex(s) => s.write_async().await,
+            #[cfg(target_arch = "wasm32")]
+            AsyncTarget::Sync(_) | AsyncTarget::Mutex(_) => retry(|| self.try_write()).await,
         }
     }
-}
 
-#[cfg(feature = "async")]
-impl<T> Clone for AsyncShared<T> {
-    fn clone(&self) -> Self {
-        AsyncShared {
-            inner: Arc::clone(&self.inner),
+    fn try_read(self) -> Result<AsyncReadGuard<'a, T>, AccessError> {
+        match self {
+            AsyncTarget::Async(a) => a.try_read_async(),
+            AsyncTarget::AsyncMutex(m) => m.try_lock().map(|guard| {
+                let guard = SyncWriteGuard::downgrade(AsyncMutexGuard::into_sync(guard));
+                AsyncReadGuard(AsyncReadLock::Shared(guard))
+            }),
+            AsyncTarget::Sync(s) => s
+                .try_read()
+                .map(|guard| AsyncReadGuard(AsyncReadLock::Shared(guard))),
+            AsyncTarget::Mutex(s) => s
+                .try_read()
+                .map(|guard| AsyncReadGuard(AsyncReadLock::Shared(guard))),
+        }
+    }
+
+    fn try_write(self) -> Result<AsyncWriteGuard<'a, T>, AccessError> {
+        match self {
+            AsyncTarget::Async(a) => a.try_write_async(),
+            AsyncTarget::AsyncMutex(m) => m.try_lock().map(|guard| {
+                AsyncWriteGuard(AsyncWriteLock::Shared(AsyncMutexGuard::into_sync(guard)))
+            }),
+            AsyncTarget::Sync(s) => s
+                .try_write()
+                .map(|guard| AsyncWriteGuard(AsyncWriteLock::Shared(guard))),
+            AsyncTarget::Mutex(s) => s
+                .try_write()
+                .map(|guard| AsyncWriteGuard(AsyncWriteLock::Shared(guard))),
+        }
+    }
+
+    /// Returns the address of the locked value's allocation, which identifies the
+    /// container and orders locks taken together.
+    fn address(self) -> usize {
+        match self {
+            AsyncTarget::Async(a) => Arc::as_ptr(&a.inner) as *const () as usize,
+            AsyncTarget::AsyncMutex(m) => Arc::as_ptr(&m.inner) as *const () as usize,
+            AsyncTarget::Sync(s) => std::ptr::from_ref(&*s.inner) as *const () as usize,
+            AsyncTarget::Mutex(s) => std::ptr::from_ref(&*s.inner) as *const () as usize,
         }
     }
 }
 
-#[cfg(feature = "async")]
-impl<T> WeakAsyncShared<T> {
-    /// Attempts to upgrade the weak reference to a strong reference.
-    pub fn upgrade(&self) -> Option<AsyncShared<T>> {
-        self.inner.upgrade().map(|inner| AsyncShared { inner })
+/// Retries `attempt` until it succeeds, letting other tasks run in between.
+///
+/// A borrow flag on wasm32 has nobody to wake its waiters, but it can only be held by
+/// another task on the same thread, which gets to run while this one yields.
+#[cfg(all(feature = "async", target_arch = "wasm32"))]
+fn retry<R>(
+    mut attempt: impl FnMut() -> Result<R, AccessError>,
+) -> impl std::future::Future<Output = R> {
+    std::future::poll_fn(move |cx| match attempt() {
+        Ok(value) => std::task::Poll::Ready(value),
+        Err(_) => {
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    })
+}
+
+/// A future of [`SharedAny`]'s [`AsyncAccess`] methods that borrows a sync container.
+///
+/// The `Rc` behind [`Shared`] on wasm32 makes such a future `!Send`, although it
+/// never leaves the one thread there is.
+#[cfg(all(feature = "async", target_arch = "wasm32"))]
+struct SingleThreaded<F>(F);
+
+// SAFETY: the crate treats wasm32 as single-threaded, which is what lets `Shared`
+// use an `Rc` and its lock a `Cell` there, so the future is never polled or dropped
+// on another thread.
+#[cfg(all(feature = "async", target_arch = "wasm32"))]
+unsafe impl<F> Send for SingleThreaded<F> {}
+
+#[cfg(all(feature = "async", target_arch = "wasm32"))]
+impl<F: std::future::Future> std::future::Future for SingleThreaded<F> {
+    type Output = F::Output;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<F::Output> {
+        // SAFETY: the inner future is never moved out of the pinned wrapper.
+        unsafe { self.map_unchecked_mut(|this| &mut this.0) }.poll(cx)
     }
 }
 
+/// Lets the compiler check on native targets that a future of [`SharedAny`] is
+/// `Send`, and wraps it on wasm32, where it can't be.
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+fn single_threaded<F: std::future::Future + Send>(future: F) -> F {
+    future
+}
+
+#[cfg(all(feature = "async", target_arch = "wasm32"))]
+fn single_threaded<F: std::future::Future>(future: F) -> SingleThreaded<F> {
+    SingleThreaded(future)
+}
+
 #[cfg(feature = "async")]
-impl<T> Clone for WeakAsyncShared<T> {
-    fn clone(&self) -> Self {
-        WeakAsyncShared {
-            inner: self.inner.clone(),
+impl<T> SharedAny<T> {
+    /// Returns the container to lock from a future.
+    fn async_target(&self) -> AsyncTarget<'_, T> {
+        match self {
+            SharedAny::Async(a) => AsyncTarget::Async(a),
+            SharedAny::AsyncMutex(m) => AsyncTarget::AsyncMutex(m),
+            SharedAny::Sync(s) => AsyncTarget::Sync(s),
+            SharedAny::Mutex(m) => AsyncTarget::Mutex(&m.inner),
         }
     }
 }
 
-// ============================================================================
-// AsyncAccess implementation for AsyncShared<T>
-// ============================================================================
-
 #[cfg(feature = "async")]
-impl<T: Send + Sync> AsyncAccess<T> for AsyncShared<T> {
-    async fn read_async<'a>(&'a self) -> AsyncReadGuard<'a, T>
+impl<T: Send + Sync> AsyncAccess<T> for SharedAny<T> {
+    fn read_async<'a>(&'a self) -> impl std::future::Future<Output = AsyncReadGuard<'a, T>> + Send
     where
         T: 'a,
     {
-        AsyncReadGuard(self.inner.read().await)
+        single_threaded(self.async_target().read())
     }
 
-    async fn write_async<'a>(&'a self) -> AsyncWriteGuard<'a, T>
+    fn write_async<'a>(&'a self) -> impl std::future::Future<Output = AsyncWriteGuard<'a, T>> + Send
     where
         T: 'a,
     {
-        AsyncWriteGuard(self.inner.write().await)
+        single_threaded(self.async_target().write())
     }
 
-    async fn get_cloned_async(&self) -> T
+    fn get_cloned_async(&self) -> impl std::future::Future<Output = T> + Send
     where
         T: Clone,
     {
-        let guard = self.inner.read().await;
-        (*guard).clone()
+        let read = self.async_target().read();
+        single_threaded(async move { (*read.await).clone() })
+    }
+
+    fn try_read_async(&self) -> Result<AsyncReadGuard<'_, T>, AccessError> {
+        self.async_target().try_read()
+    }
+
+    fn try_write_async(&self) -> Result<AsyncWriteGuard<'_, T>, AccessError> {
+        self.async_target().try_write()
+    }
+
+    fn swap_async<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> impl std::future::Future<Output = ()> + Send + 'a
+    where
+        T: 'a,
+    {
+        let (this, that) = (self.async_target(), other.async_target());
+        single_threaded(async move {
+            if this.address() == that.address() {
+                return;
+            }
+            // Lock in address order so that concurrent swaps cannot deadlock
+            let (first, second) = if this.address() < that.address() {
+                (this, that)
+            } else {
+                (that, this)
+            };
+            let mut first = first.write().await;
+            let mut second = second.write().await;
+            std::mem::swap(&mut *first, &mut *second);
+        })
     }
 }
 
 // ============================================================================
-// Conversions for SharedAny
+// LocalSharedAny
 // ============================================================================
 
-// Conversions for SharedAny
-impl<T> From<Shared<T>> for SharedAny<T> {
+impl<T> From<LocalShared<T>> for LocalSharedAny<T> {
+    fn from(shared: LocalShared<T>) -> Self {
+        LocalSharedAny::Local(shared)
+    }
+}
+
+impl<T> From<SharedAny<T>> for LocalSharedAny<T> {
+    fn from(shared: SharedAny<T>) -> Self {
+        LocalSharedAny::Any(shared)
+    }
+}
+
+impl<T> From<Shared<T>> for LocalSharedAny<T> {
     fn from(shared: Shared<T>) -> Self {
-        SharedAny::Sync(shared)
+        LocalSharedAny::Any(shared.into())
+    }
+}
+
+impl<T> From<SharedMutex<T>> for LocalSharedAny<T> {
+    fn from(shared: SharedMutex<T>) -> Self {
+        LocalSharedAny::Any(shared.into())
     }
 }
 
 #[cfg(feature = "async")]
-impl<T> From<AsyncShared<T>> for SharedAny<T> {
+impl<T> From<AsyncShared<T>> for LocalSharedAny<T> {
     fn from(shared: AsyncShared<T>) -> Self {
-        SharedAny::Async(shared)
+        LocalSharedAny::Any(shared.into())
     }
 }
 
-impl<T> Clone for SharedAny<T> {
+#[cfg(feature = "async")]
+impl<T> From<AsyncSharedMutex<T>> for LocalSharedAny<T> {
+    fn from(shared: AsyncSharedMutex<T>) -> Self {
+        LocalSharedAny::Any(shared.into())
+    }
+}
+
+impl<T> Clone for LocalSharedAny<T> {
     fn clone(&self) -> Self {
         match self {
-            SharedAny::Sync(s) => SharedAny::Sync(s.clone()),
-            #[cfg(feature = "async")]
-            SharedAny::Async(a) => SharedAny::Async(a.clone()),
+            LocalSharedAny::Local(l) => LocalSharedAny::Local(l.clone()),
+            LocalSharedAny::Any(a) => LocalSharedAny::Any(a.clone()),
         }
     }
 }
 
-impl<T> SharedAny<T> {
+/// Shows the variant around the `Debug` output of the container it holds.
+impl<T: std::fmt::Debug> std::fmt::Debug for LocalSharedAny<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalSharedAny::Local(l) => f.debug_tuple("Local").field(l).finish(),
+            LocalSharedAny::Any(a) => f.debug_tuple("Any").field(a).finish(),
+        }
+    }
+}
+
+impl<T: Default> Default for LocalSharedAny<T> {
+    fn default() -> Self {
+        LocalSharedAny::Local(LocalShared::default())
+    }
+}
+
+impl<T> LocalSharedAny<T> {
     /// Creates a weak reference to this container.
-    pub fn downgrade(&self) -> WeakSharedAny<T> {
+    pub fn downgrade(&self) -> WeakLocalSharedAny<T> {
         match self {
-            SharedAny::Sync(s) => WeakSharedAny::Sync(s.downgrade()),
-            #[cfg(feature = "async")]
-            SharedAny::Async(a) => WeakSharedAny::Async(a.downgrade()),
+            LocalSharedAny::Local(l) => WeakLocalSharedAny::Local(l.downgrade()),
+            LocalSharedAny::Any(a) => WeakLocalSharedAny::Any(a.downgrade()),
+        }
+    }
+
+    /// Returns the value if this is the only strong handle, or the handle otherwise.
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        match self {
+            LocalSharedAny::Local(l) => l.try_unwrap().map_err(LocalSharedAny::Local),
+            LocalSharedAny::Any(a) => a.try_unwrap().map_err(LocalSharedAny::Any),
+        }
+    }
+
+    /// Returns the value if this is the only strong handle, dropping the handle
+    /// otherwise.
+    ///
+    /// See [`SharedAny::into_inner`].
+    pub fn into_inner(self) -> Result<Option<T>, PoisonError<T>> {
+        match self {
+            LocalSharedAny::Local(l) => Ok(l.into_inner()),
+            LocalSharedAny::Any(a) => a.into_inner(),
+        }
+    }
+
+    /// Returns whether both handles belong to the same container.
+    ///
+    /// See [`Shared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        match self {
+            LocalSharedAny::Local(l) => l.strong_count(),
+            LocalSharedAny::Any(a) => a.strong_count(),
+        }
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        match self {
+            LocalSharedAny::Local(l) => l.weak_count(),
+            LocalSharedAny::Any(a) => a.weak_count(),
+        }
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`Shared::id`].
+    pub fn id(&self) -> ContainerId {
+        match self {
+            LocalSharedAny::Local(l) => l.id(),
+            LocalSharedAny::Any(a) => a.id(),
         }
     }
 }
 
-impl<T> WeakSharedAny<T> {
+impl<T> WeakLocalSharedAny<T> {
     /// Attempts to upgrade the weak reference to a strong reference.
-    pub fn upgrade(&self) -> Option<SharedAny<T>> {
+    pub fn upgrade(&self) -> Option<LocalSharedAny<T>> {
         match self {
-            WeakSharedAny::Sync(w) => w.upgrade().map(SharedAny::Sync),
-            #[cfg(feature = "async")]
-            WeakSharedAny::Async(w) => w.upgrade().map(SharedAny::Async),
+            WeakLocalSharedAny::Local(w) => w.upgrade().map(LocalSharedAny::Local),
+            WeakLocalSharedAny::Any(w) => w.upgrade().map(LocalSharedAny::Any),
+        }
+    }
+
+    /// Returns whether both weak references belong to the same container.
+    ///
+    /// See [`WeakShared::ptr_eq`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+
+    /// Returns the number of strong handles to the container.
+    pub fn strong_count(&self) -> usize {
+        match self {
+            WeakLocalSharedAny::Local(w) => w.strong_count(),
+            WeakLocalSharedAny::Any(w) => w.strong_count(),
+        }
+    }
+
+    /// Returns the number of weak references to the container.
+    pub fn weak_count(&self) -> usize {
+        match self {
+            WeakLocalSharedAny::Local(w) => w.weak_count(),
+            WeakLocalSharedAny::Any(w) => w.weak_count(),
+        }
+    }
+
+    /// Returns the identity of the container.
+    ///
+    /// See [`WeakShared::id`].
+    pub fn id(&self) -> ContainerId {
+        match self {
+            WeakLocalSharedAny::Local(w) => w.id(),
+            WeakLocalSharedAny::Any(w) => w.id(),
         }
     }
 }
 
-impl<T> Clone for WeakSharedAny<T> {
+impl<T> Clone for WeakLocalSharedAny<T> {
     fn clone(&self) -> Self {
         match self {
-            WeakSharedAny::Sync(w) => WeakSharedAny::Sync(w.clone()),
-            #[cfg(feature = "async")]
-            WeakSharedAny::Async(w) => WeakSharedAny::Async(w.clone()),
+            WeakLocalSharedAny::Local(w) => WeakLocalSharedAny::Local(w.clone()),
+            WeakLocalSharedAny::Any(w) => WeakLocalSharedAny::Any(w.clone()),
         }
     }
 }
 
-// ============================================================================
-// Trait implementations for SharedAny<T>
-// ============================================================================
+impl<T> Identity for LocalSharedAny<T> {
+    fn id(&self) -> ContainerId {
+        LocalSharedAny::id(self)
+    }
+}
 
-impl<T> SyncAccess<T> for SharedAny<T> {
+impl<T> Identity for WeakLocalSharedAny<T> {
+    fn id(&self) -> ContainerId {
+        WeakLocalSharedAny::id(self)
+    }
+}
+
+impl<T> ReadValue<T> for LocalSharedAny<T> {
+    fn read_value(&self) -> Result<impl Deref<Target = T> + '_, AccessError> {
+        match self {
+            LocalSharedAny::Local(l) => l.read(),
+            LocalSharedAny::Any(a) => a.read_value(),
+        }
+    }
+}
+
+impl<T> LocalSharedAny<T> {
+    /// Whether `PartialEq` and `PartialOrd` may read the value, as for [`SharedAny`].
+    fn compares_by_value(&self) -> bool {
+        match self {
+            LocalSharedAny::Local(_) => true,
+            LocalSharedAny::Any(a) => a.compares_by_value(),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for LocalSharedAny<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.compares_by_value()
+            && other.compares_by_value()
+            && compare_values(self, other, T::eq).unwrap_or(false)
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for LocalSharedAny<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        if !(self.compares_by_value() && other.compares_by_value()) {
+            return None;
+        }
+        compare_values(self, other, T::partial_cmp).ok().flatten()
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for LocalSharedAny<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        display_value(self, f)
+    }
+}
+
+impl<T> SyncAccess<T> for LocalSharedAny<T> {
     fn read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
         match self {
-            SharedAny::Sync(s) => s.read(),
-            #[cfg(feature = "async")]
-            SharedAny::Async(_) => Err(AccessError::UnsupportedMode),
+            LocalSharedAny::Local(l) => l.read(),
+            LocalSharedAny::Any(a) => a.read(),
         }
     }
 
+    #[track_caller]
     fn write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
         match self {
-            SharedAny::Sync(s) => s.write(),
-            #[cfg(feature = "async")]
-            SharedAny::Async(_) => Err(AccessError::UnsupportedMode),
+            LocalSharedAny::Local(l) => l.write(),
+            LocalSharedAny::Any(a) => a.write(),
         }
     }
 
-    fn get_cloned(&self) -> Result<T, AccessError>
-    where
-        T: Clone,
-    {
+    fn try_read(&self) -> Result<SyncReadGuard<'_, T>, AccessError> {
         match self {
-            SharedAny::Sync(s) => s.get_cloned(),
-            #[cfg(feature = "async")]
-            SharedAny::Async(_) => Err(AccessError::UnsupportedMode),
+            LocalSharedAny::Local(l) => l.try_read(),
+            LocalSharedAny::Any(a) => a.try_read(),
         }
     }
-}
 
-#[cfg(feature = "async")]
-impl<T: Send + Sync> AsyncAccess<T> for SharedAny<T> {
-    async fn read_async<'a>(&'a self) -> AsyncReadGuard<'a, T>
-    where
-        T: 'a,
-    {
+    #[track_caller]
+    fn try_write(&self) -> Result<SyncWriteGuard<'_, T>, AccessError> {
         match self {
-            SharedAny::Async(a) => a.read_async().await,
-            SharedAny::Sync(_) => {
-                // This branch should not be reachable in normal usage,
-                // as the type system should prevent it. However, we need
-                // to provide a return value for the compiler.
-                unreachable!("Cannot call async methods on sync container")
-            }
+            LocalSharedAny::Local(l) => l.try_write(),
+            LocalSharedAny::Any(a) => a.try_write(),
         }
     }
 
-    async fn write_async<'a>(&'a self) -> AsyncWriteGuard<'a, T>
-    where
-        T: 'a,
-    {
-        match self {
-            SharedAny::Async(a) => a.write_async().await,
-            SharedAny::Sync(_) => {
-                unreachable!("Cannot call async methods on sync container")
-            }
+    #[track_caller]
+    fn swap(&self, other: &Self) -> Result<(), AccessError> {
+        match (self, other) {
+            (LocalSharedAny::Local(a), LocalSharedAny::Local(b)) => a.swap(b),
+            (LocalSharedAny::Any(a), LocalSharedAny::Any(b)) => a.swap(b),
+            _ => Err(AccessError::UnsupportedMode),
         }
     }
 
-    async fn get_cloned_async(&self) -> T
+    fn get_cloned(&self) -> Result<T, AccessError>
     where
         T: Clone,
     {
         match self {
-            SharedAny::Async(a) => a.get_cloned_async().await,
-            SharedAny::Sync(_) => {
-                unreachable!("Cannot call async methods on sync container")
-            }
+            LocalSharedAny::Local(l) => l.get_cloned(),
+            LocalSharedAny::Any(a) => a.get_cloned(),
         }
     }
 }
 
 #[cfg(test)]
+#[allow(deprecated)]
 mod tests {
 
     #[derive(Debug, Clone, PartialEq)]
@@ -1572,6 +8496,7 @@ mod tests {
 // Tests specifically for the tokio async implementation
 #[cfg(test)]
 #[cfg(feature = "tokio-sync")]
+#[allow(deprecated)]
 mod tokio_tests {
     use super::*;
     use tokio::runtime::Runtime;
@@ -1674,6 +8599,7 @@ mod tokio_tests {
 // These tests can be run on any platform by enabling the force-wasm-impl feature
 #[cfg(test)]
 #[cfg(any(target_arch = "wasm32", feature = "force-wasm-impl"))]
+#[allow(deprecated)]
 mod wasm_tests {
     use super::*;
 
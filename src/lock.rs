@@ -0,0 +1,1309 @@
+//! Lock backends for `Shared<T>` and the bookkeeping the container keeps next to
+//! them.
+//!
+//! Backends only track lock state; the data itself lives next to them in the
+//! container and is handed out through the guards defined in the crate root.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use crate::MaybeSendSync;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::DefaultBackend;
+
+#[cfg(target_arch = "wasm32")]
+pub use local::BorrowFlag as DefaultBackend;
+
+pub(crate) use local::BorrowFlag;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use native::{Poison, WriteSignal};
+
+/// The lock a `Shared<T, B>` keeps next to its data.
+///
+/// With the `async` feature on native targets the backend is wrapped so that tasks
+/// waiting for it are woken when it is released.
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+pub(crate) type ContainerLock<B> = native::Waking<B>;
+
+#[cfg(not(all(feature = "async", not(target_arch = "wasm32"))))]
+pub(crate) type ContainerLock<B> = B;
+
+#[cfg(feature = "backend-parking-lot")]
+pub use parking::ParkingLotBackend;
+
+pub use mutex::MutexBackend;
+
+pub(crate) use local_async::LocalAsyncLock;
+
+#[cfg(feature = "backend-spin")]
+pub use spin::SpinBackend;
+
+/// A raw reader-writer lock that a [`Shared`](crate::Shared) container is built on.
+///
+/// Besides the shared and exclusive modes, a backend offers an upgradable mode: an
+/// upgradable holder coexists with shared holders, excludes exclusive and other
+/// upgradable holders, and can be turned into the exclusive holder without letting
+/// a writer in first. A backend that cannot share may treat every mode as exclusive.
+///
+/// Backends only track lock state. Poisoning, split guards, write observers and
+/// [`Shared::wait_until`](crate::Shared::wait_until) are handled by the container and
+/// work the same on every backend.
+///
+/// On wasm32 the container only uses the non-blocking `try_*` methods, since a
+/// conflicting lock can never be released while the caller waits.
+///
+/// # Safety
+///
+/// `Shared` relies on the backend for the soundness of its guards. While the lock
+/// is held exclusively there must be no other holder of any mode, and while it is
+/// held in shared or upgradable mode there must be no exclusive holder.
+///
+/// The container only calls the methods that release or convert a lock on behalf
+/// of a current holder of the matching mode, but possibly from a different thread
+/// than the one that acquired it.
+pub unsafe trait LockBackend: MaybeSendSync + 'static {
+    /// Creates an unlocked lock.
+    fn new() -> Self
+    where
+        Self: Sized;
+
+    /// Acquires a shared lock, blocking until it is available.
+    fn lock_shared(&self);
+
+    /// Acquires a shared lock if that is possible without blocking.
+    fn try_lock_shared(&self) -> bool;
+
+    /// Releases a shared lock.
+    fn unlock_shared(&self);
+
+    /// Acquires the exclusive lock, blocking until it is available.
+    fn lock_exclusive(&self);
+
+    /// Acquires the exclusive lock if that is possible without blocking.
+    fn try_lock_exclusive(&self) -> bool;
+
+    /// Releases the exclusive lock.
+    fn unlock_exclusive(&self);
+
+    /// Atomically turns the exclusive lock into a shared lock.
+    fn downgrade(&self);
+
+    /// Acquires the upgradable lock, blocking until it is available.
+    fn lock_upgradable(&self);
+
+    /// Acquires the upgradable lock if that is possible without blocking.
+    fn try_lock_upgradable(&self) -> bool;
+
+    /// Releases the upgradable lock.
+    fn unlock_upgradable(&self);
+
+    /// Turns the upgradable lock into the exclusive lock, blocking until the shared
+    /// holders have left.
+    fn upgrade(&self);
+
+    /// Turns the upgradable lock into the exclusive lock if no shared holders are
+    /// active.
+    fn try_upgrade(&self) -> bool;
+
+    /// Atomically turns the upgradable lock into a shared lock.
+    fn downgrade_upgradable(&self);
+
+    /// Acquires a shared lock, giving up once `deadline` has passed.
+    ///
+    /// The default implementation polls [`try_lock_shared`](Self::try_lock_shared),
+    /// yielding to other threads in between.
+    fn lock_shared_until(&self, deadline: Instant) -> bool {
+        poll_until(deadline, || self.try_lock_shared())
+    }
+
+    /// Acquires the exclusive lock, giving up once `deadline` has passed.
+    ///
+    /// The default implementation polls
+    /// [`try_lock_exclusive`](Self::try_lock_exclusive), yielding to other threads in
+    /// between.
+    fn lock_exclusive_until(&self, deadline: Instant) -> bool {
+        poll_until(deadline, || self.try_lock_exclusive())
+    }
+
+    /// Records that a task waits for the exclusive lock without blocking a thread,
+    /// so that a backend which prefers writers can hold back new shared holders in
+    /// the meantime.
+    ///
+    /// The container follows every call with one call to
+    /// [`withdraw_exclusive`](Self::withdraw_exclusive), once the task has acquired
+    /// the lock or stopped waiting. The default implementation does nothing, so
+    /// `write_async` may wait for as long as new readers keep arriving.
+    fn announce_exclusive(&self) {}
+
+    /// Withdraws an earlier [`announce_exclusive`](Self::announce_exclusive).
+    fn withdraw_exclusive(&self) {}
+}
+
+fn poll_until(deadline: Instant, mut attempt: impl FnMut() -> bool) -> bool {
+    loop {
+        if attempt() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::yield_now();
+    }
+}
+
+/// Counts the extra guards sharing one acquisition of a backend lock, which exist
+/// once a guard has been split with `map_split`.
+///
+/// Only the last guard releases the backend lock. Shared acquisitions are
+/// interchangeable, so any shared guard may use up an extra count.
+#[derive(Debug, Default)]
+pub(crate) struct Holders {
+    shared: AtomicUsize,
+    exclusive: AtomicUsize,
+}
+
+impl Holders {
+    /// Adds another holder to a shared lock the caller already holds.
+    pub(crate) fn share_shared(&self) {
+        self.shared.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Removes one holder of a shared lock.
+    ///
+    /// Returns `true` if the caller must release the backend lock.
+    pub(crate) fn leave_shared(&self) -> bool {
+        Self::leave(&self.shared)
+    }
+
+    /// Adds another holder to the exclusive lock the caller already holds.
+    pub(crate) fn share_exclusive(&self) {
+        self.exclusive.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Removes one holder of the exclusive lock.
+    ///
+    /// Returns `true` if the caller is the last holder and must release the lock.
+    pub(crate) fn leave_exclusive(&self) -> bool {
+        Self::leave(&self.exclusive)
+    }
+
+    fn leave(extra: &AtomicUsize) -> bool {
+        // Acquire-release so that every holder's accesses happen before the final
+        // release of the backend lock.
+        extra
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+            .is_err()
+    }
+}
+
+/// Reader-writer lock built on `std::sync::Mutex` and `Condvar`, plus the
+/// container's native-only bookkeeping.
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+    use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+    #[cfg(feature = "async")]
+    use std::task::{Poll, Waker};
+    use std::time::Instant;
+
+    use super::LockBackend;
+    use crate::PoisonInfo;
+
+    /// Locks a mutex that is never held across user code, so poisoning carries no
+    /// information.
+    fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+        mutex.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Waits for `condvar`. Returns `true` in the second slot if the deadline passed
+    /// before the wait started.
+    fn wait<'a, T>(
+        condvar: &Condvar,
+        guard: MutexGuard<'a, T>,
+        deadline: Option<Instant>,
+    ) -> (MutexGuard<'a, T>, bool) {
+        match deadline {
+            None => (
+                condvar.wait(guard).unwrap_or_else(PoisonError::into_inner),
+                false,
+            ),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return (guard, true);
+                }
+                let (guard, _) = condvar
+                    .wait_timeout(guard, deadline - now)
+                    .unwrap_or_else(PoisonError::into_inner);
+                (guard, false)
+            }
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct State {
+        /// Number of active shared holders.
+        readers: usize,
+        /// Whether the exclusive lock is held.
+        writer: bool,
+        /// Number of threads waiting for exclusive access, including an
+        /// upgradable holder waiting to upgrade.
+        writers_waiting: usize,
+        /// Whether an upgradable reader is active. It coexists with plain readers
+        /// but excludes writers and other upgradable readers.
+        upgradable: bool,
+    }
+
+    /// The default backend on native targets.
+    ///
+    /// Unlike `std::sync::RwLock` this lock can wait for a deadline without polling
+    /// and supports the upgradable mode. It prefers writers: once a writer is
+    /// waiting, new readers queue behind it.
+    #[derive(Debug, Default)]
+    pub struct DefaultBackend {
+        state: Mutex<State>,
+        changed: Condvar,
+    }
+
+    impl DefaultBackend {
+        fn state(&self) -> MutexGuard<'_, State> {
+            lock(&self.state)
+        }
+
+        fn lock_shared_inner(&self, deadline: Option<Instant>) -> bool {
+            let mut state = self.state();
+            while state.writer || state.writers_waiting > 0 {
+                let (next, timed_out) = wait(&self.changed, state, deadline);
+                if timed_out {
+                    return false;
+                }
+                state = next;
+            }
+            state.readers += 1;
+            true
+        }
+
+        fn lock_exclusive_inner(&self, deadline: Option<Instant>) -> bool {
+            let mut state = self.state();
+            state.writers_waiting += 1;
+            while state.writer || state.readers > 0 || state.upgradable {
+                let (next, timed_out) = wait(&self.changed, state, deadline);
+                state = next;
+                if timed_out {
+                    state.writers_waiting -= 1;
+                    drop(state);
+                    // Readers may have been queued behind us.
+                    self.changed.notify_all();
+                    return false;
+                }
+            }
+            state.writers_waiting -= 1;
+            state.writer = true;
+            true
+        }
+    }
+
+    // SAFETY: the state checks below enforce the exclusion rules.
+    unsafe impl LockBackend for DefaultBackend {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn lock_shared(&self) {
+            self.lock_shared_inner(None);
+        }
+
+        fn try_lock_shared(&self) -> bool {
+            let mut state = self.state();
+            if state.writer || state.writers_waiting > 0 {
+                return false;
+            }
+            state.readers += 1;
+            true
+        }
+
+        fn unlock_shared(&self) {
+            let mut state = self.state();
+            state.readers -= 1;
+            let release = state.readers == 0;
+            drop(state);
+            if release {
+                self.changed.notify_all();
+            }
+        }
+
+        fn lock_exclusive(&self) {
+            self.lock_exclusive_inner(None);
+        }
+
+        fn try_lock_exclusive(&self) -> bool {
+            let mut state = self.state();
+            if state.writer || state.readers > 0 || state.upgradable {
+                return false;
+            }
+            state.writer = true;
+            true
+        }
+
+        fn unlock_exclusive(&self) {
+            self.state().writer = false;
+            self.changed.notify_all();
+        }
+
+        fn downgrade(&self) {
+            let mut state = self.state();
+            state.writer = false;
+            state.readers += 1;
+            drop(state);
+            self.changed.notify_all();
+        }
+
+        fn lock_upgradable(&self) {
+            let mut state = self.state();
+            while state.writer || state.writers_waiting > 0 || state.upgradable {
+                state = wait(&self.changed, state, None).0;
+            }
+            state.upgradable = true;
+        }
+
+        fn try_lock_upgradable(&self) -> bool {
+            let mut state = self.state();
+            if state.writer || state.writers_waiting > 0 || state.upgradable {
+                return false;
+            }
+            state.upgradable = true;
+            true
+        }
+
+        fn unlock_upgradable(&self) {
+            self.state().upgradable = false;
+            self.changed.notify_all();
+        }
+
+        /// Waits for plain readers to leave. New readers are held back in the
+        /// meantime.
+        fn upgrade(&self) {
+            let mut state = self.state();
+            state.writers_waiting += 1;
+            while state.readers > 0 {
+                state = wait(&self.changed, state, None).0;
+            }
+            state.writers_waiting -= 1;
+            state.upgradable = false;
+            state.writer = true;
+        }
+
+        fn try_upgrade(&self) -> bool {
+            let mut state = self.state();
+            if state.readers > 0 {
+                return false;
+            }
+            state.upgradable = false;
+            state.writer = true;
+            true
+        }
+
+        fn downgrade_upgradable(&self) {
+            let mut state = self.state();
+            state.upgradable = false;
+            state.readers += 1;
+            drop(state);
+            self.changed.notify_all();
+        }
+
+        fn lock_shared_until(&self, deadline: Instant) -> bool {
+            self.lock_shared_inner(Some(deadline))
+        }
+
+        fn lock_exclusive_until(&self, deadline: Instant) -> bool {
+            self.lock_exclusive_inner(Some(deadline))
+        }
+
+        fn announce_exclusive(&self) {
+            self.state().writers_waiting += 1;
+        }
+
+        fn withdraw_exclusive(&self) {
+            self.state().writers_waiting -= 1;
+            // Readers may have been queued behind the task.
+            self.changed.notify_all();
+        }
+    }
+
+    /// A backend that wakes the tasks waiting for it whenever a holder leaves.
+    ///
+    /// Tasks take the lock with the `try_*` methods, so an executor thread never
+    /// blocks on it.
+    #[cfg(feature = "async")]
+    #[derive(Debug)]
+    pub(crate) struct Waking<B> {
+        backend: B,
+        /// Whether `wakers` may be non-empty, so releases skip the mutex otherwise.
+        pending: AtomicBool,
+        wakers: Mutex<Vec<Waker>>,
+    }
+
+    #[cfg(feature = "async")]
+    impl<B: LockBackend> Waking<B> {
+        /// Waits until `try_lock` succeeds, retrying after every release.
+        pub(crate) async fn acquire(&self, try_lock: impl Fn(&B) -> bool) {
+            std::future::poll_fn(|cx| {
+                if try_lock(&self.backend) {
+                    return Poll::Ready(());
+                }
+                self.register(cx.waker());
+                // Retry once registered, or a release in between would go unnoticed.
+                if try_lock(&self.backend) {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await
+        }
+
+        /// Waits for the exclusive lock, announcing the task to the backend so that
+        /// new readers queue behind it.
+        pub(crate) async fn acquire_exclusive(&self) {
+            if self.backend.try_lock_exclusive() {
+                return;
+            }
+            self.backend.announce_exclusive();
+            // Withdraws the announcement even if the future is dropped while waiting.
+            let _announced = Announced(self);
+            self.acquire(B::try_lock_exclusive).await;
+        }
+
+        fn register(&self, waker: &Waker) {
+            let mut wakers = lock(&self.wakers);
+            if !wakers.iter().any(|known| known.will_wake(waker)) {
+                wakers.push(waker.clone());
+            }
+            self.pending.store(true, Ordering::SeqCst);
+            drop(wakers);
+            // Pairs with the fence in `wake`: either the retry sees the release, or
+            // the releaser sees `pending`.
+            std::sync::atomic::fence(Ordering::SeqCst);
+        }
+
+        /// Wakes every waiting task, since any of them may now get the lock.
+        fn wake(&self) {
+            std::sync::atomic::fence(Ordering::SeqCst);
+            if !self.pending.load(Ordering::SeqCst) {
+                return;
+            }
+            let wakers = {
+                let mut wakers = lock(&self.wakers);
+                self.pending.store(false, Ordering::SeqCst);
+                std::mem::take(&mut *wakers)
+            };
+            wakers.into_iter().for_each(Waker::wake);
+        }
+    }
+
+    /// Withdraws an announced exclusive waiter when dropped.
+    #[cfg(feature = "async")]
+    struct Announced<'a, B: LockBackend>(&'a Waking<B>);
+
+    #[cfg(feature = "async")]
+    impl<B: LockBackend> Drop for Announced<'_, B> {
+        fn drop(&mut self) {
+            self.0.withdraw_exclusive();
+        }
+    }
+
+    // SAFETY: every method delegates to the wrapped backend, which enforces the
+    // exclusion rules.
+    #[cfg(feature = "async")]
+    unsafe impl<B: LockBackend> LockBackend for Waking<B> {
+        fn new() -> Self {
+            Waking {
+                backend: B::new(),
+                pending: AtomicBool::new(false),
+                wakers: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn lock_shared(&self) {
+            self.backend.lock_shared();
+        }
+
+        fn try_lock_shared(&self) -> bool {
+            self.backend.try_lock_shared()
+        }
+
+        fn unlock_shared(&self) {
+            self.backend.unlock_shared();
+            self.wake();
+        }
+
+        fn lock_exclusive(&self) {
+            self.backend.lock_exclusive();
+        }
+
+        fn try_lock_exclusive(&self) -> bool {
+            self.backend.try_lock_exclusive()
+        }
+
+        fn unlock_exclusive(&self) {
+            self.backend.unlock_exclusive();
+            self.wake();
+        }
+
+        fn downgrade(&self) {
+            self.backend.downgrade();
+            self.wake();
+        }
+
+        fn lock_upgradable(&self) {
+            self.backend.lock_upgradable();
+        }
+
+        fn try_lock_upgradable(&self) -> bool {
+            self.backend.try_lock_upgradable()
+        }
+
+        fn unlock_upgradable(&self) {
+            self.backend.unlock_upgradable();
+            self.wake();
+        }
+
+        fn upgrade(&self) {
+            self.backend.upgrade();
+        }
+
+        fn try_upgrade(&self) -> bool {
+            self.backend.try_upgrade()
+        }
+
+        fn downgrade_upgradable(&self) {
+            self.backend.downgrade_upgradable();
+            self.wake();
+        }
+
+        fn lock_shared_until(&self, deadline: Instant) -> bool {
+            self.backend.lock_shared_until(deadline)
+        }
+
+        fn lock_exclusive_until(&self, deadline: Instant) -> bool {
+            let acquired = self.backend.lock_exclusive_until(deadline);
+            if !acquired {
+                // Tasks may have been held back while the thread waited.
+                self.wake();
+            }
+            acquired
+        }
+
+        fn announce_exclusive(&self) {
+            self.backend.announce_exclusive();
+        }
+
+        fn withdraw_exclusive(&self) {
+            self.backend.withdraw_exclusive();
+            self.wake();
+        }
+    }
+
+    /// Records whether, and by whom, a container was poisoned.
+    #[derive(Debug, Default)]
+    pub(crate) struct Poison {
+        poisoned: AtomicBool,
+        /// Who poisoned the container; set whenever `poisoned` is.
+        info: Mutex<Option<PoisonInfo>>,
+    }
+
+    impl Poison {
+        pub(crate) fn is_poisoned(&self) -> bool {
+            self.poisoned.load(Ordering::Relaxed)
+        }
+
+        /// Returns who poisoned the container, or `None` if it is not poisoned.
+        pub(crate) fn info(&self) -> Option<PoisonInfo> {
+            lock(&self.info).clone()
+        }
+
+        pub(crate) fn poison(&self, info: PoisonInfo) {
+            *lock(&self.info) = Some(info);
+            self.poisoned.store(true, Ordering::Relaxed);
+        }
+
+        pub(crate) fn clear(&self) {
+            *lock(&self.info) = None;
+            self.poisoned.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Lets threads wait for the next completed write, whatever the backend.
+    ///
+    /// Writers only touch the mutex while somebody is waiting.
+    #[derive(Debug, Default)]
+    pub(crate) struct WriteSignal {
+        writes: AtomicU64,
+        waiters: AtomicUsize,
+        mutex: Mutex<()>,
+        changed: Condvar,
+    }
+
+    impl WriteSignal {
+        /// Returns the number of completed writes, to be passed to `wait`.
+        ///
+        /// Read it while holding the container's lock in shared mode, so that no
+        /// write can be in progress.
+        pub(crate) fn writes(&self) -> u64 {
+            self.writes.load(Ordering::SeqCst)
+        }
+
+        /// Announces a completed write.
+        pub(crate) fn notify(&self) {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+            if self.waiters.load(Ordering::SeqCst) > 0 {
+                // A waiter that has checked `writes` holds the mutex until it
+                // sleeps, so it cannot miss the notification.
+                drop(lock(&self.mutex));
+                self.changed.notify_all();
+            }
+        }
+
+        /// Waits until a write completes after `seen` was read from `writes`.
+        ///
+        /// Returns `false` if the deadline passed first.
+        pub(crate) fn wait(&self, seen: u64, deadline: Option<Instant>) -> bool {
+            self.waiters.fetch_add(1, Ordering::SeqCst);
+            let mut guard = lock(&self.mutex);
+            let written = loop {
+                if self.writes.load(Ordering::SeqCst) != seen {
+                    break true;
+                }
+                let (next, timed_out) = wait(&self.changed, guard, deadline);
+                guard = next;
+                if timed_out {
+                    break false;
+                }
+            };
+            drop(guard);
+            self.waiters.fetch_sub(1, Ordering::SeqCst);
+            written
+        }
+    }
+}
+
+/// Single-threaded borrow flag with `RefCell` semantics.
+///
+/// Always compiled, since `LocalShared` is built on it.
+mod local {
+    use std::cell::Cell;
+
+    use super::LockBackend;
+
+    /// A `RefCell`-style borrow flag, the default backend on wasm32 and the lock of
+    /// `LocalShared` on every target.
+    ///
+    /// There is nobody to wait for on a single thread, so the blocking methods
+    /// panic instead of deadlocking if the lock is unavailable.
+    #[derive(Debug, Default)]
+    pub struct BorrowFlag {
+        /// Positive: number of shared borrows, including an upgradable one.
+        /// Negative: exclusively borrowed.
+        borrow: Cell<isize>,
+        /// Whether one of the shared borrows is upgradable.
+        upgradable: Cell<bool>,
+    }
+
+    const DEADLOCK: &str = "lock is held and can never be released while waiting";
+
+    // SAFETY: on native targets the flag only backs `LocalShared` and
+    // `ThreadBoundShared`, and its `Cell`s are only ever touched from one thread.
+    // `LocalShared`'s `Rc` keeps the flag on the thread that created it, and
+    // `ThreadBoundShared` may be shared across threads but `local()` refuses every
+    // thread except the owner before the flag is reached. `LockBackend` merely
+    // requires the bound.
+    #[cfg(not(target_arch = "wasm32"))]
+    unsafe impl Sync for BorrowFlag {}
+
+    // SAFETY: the borrow flag enforces the exclusion rules, and it is only ever used
+    // from one thread.
+    unsafe impl LockBackend for BorrowFlag {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn lock_shared(&self) {
+            assert!(self.try_lock_shared(), "{DEADLOCK}");
+        }
+
+        fn try_lock_shared(&self) -> bool {
+            let borrow = self.borrow.get();
+            if borrow < 0 {
+                return false;
+            }
+            self.borrow.set(borrow + 1);
+            true
+        }
+
+        fn unlock_shared(&self) {
+            self.borrow.set(self.borrow.get() - 1);
+        }
+
+        fn lock_exclusive(&self) {
+            assert!(self.try_lock_exclusive(), "{DEADLOCK}");
+        }
+
+        fn try_lock_exclusive(&self) -> bool {
+            if self.borrow.get() != 0 {
+                return false;
+            }
+            self.borrow.set(-1);
+            true
+        }
+
+        fn unlock_exclusive(&self) {
+            self.borrow.set(0);
+        }
+
+        fn downgrade(&self) {
+            self.borrow.set(1);
+        }
+
+        fn lock_upgradable(&self) {
+            assert!(self.try_lock_upgradable(), "{DEADLOCK}");
+        }
+
+        fn try_lock_upgradable(&self) -> bool {
+            if self.upgradable.get() || !self.try_lock_shared() {
+                return false;
+            }
+            self.upgradable.set(true);
+            true
+        }
+
+        fn unlock_upgradable(&self) {
+            self.upgradable.set(false);
+            self.unlock_shared();
+        }
+
+        fn upgrade(&self) {
+            assert!(self.try_upgrade(), "{DEADLOCK}");
+        }
+
+        fn try_upgrade(&self) -> bool {
+            if self.borrow.get() != 1 {
+                return false;
+            }
+            self.upgradable.set(false);
+            self.borrow.set(-1);
+            true
+        }
+
+        fn downgrade_upgradable(&self) {
+            self.upgradable.set(false);
+        }
+    }
+}
+
+/// Backend wrapping `parking_lot::RawRwLock`.
+#[cfg(feature = "backend-parking-lot")]
+mod parking {
+    use std::time::Instant;
+
+    use parking_lot::lock_api::{
+        RawRwLock, RawRwLockDowngrade, RawRwLockTimed, RawRwLockUpgrade, RawRwLockUpgradeDowngrade,
+    };
+
+    use super::LockBackend;
+
+    /// Backend built on `parking_lot`'s reader-writer lock.
+    ///
+    /// The lock is a single word and does not poison by itself, and it is eventually
+    /// fair instead of always preferring writers. Only available with the
+    /// `backend-parking-lot` feature flag.
+    pub struct ParkingLotBackend(parking_lot::RawRwLock);
+
+    impl std::fmt::Debug for ParkingLotBackend {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ParkingLotBackend").finish_non_exhaustive()
+        }
+    }
+
+    // SAFETY: forwards to `parking_lot`, whose locks may be released on another
+    // thread with the `send_guard` feature this crate enables.
+    unsafe impl LockBackend for ParkingLotBackend {
+        fn new() -> Self {
+            ParkingLotBackend(parking_lot::RawRwLock::INIT)
+        }
+
+        fn lock_shared(&self) {
+            self.0.lock_shared();
+        }
+
+        fn try_lock_shared(&self) -> bool {
+            self.0.try_lock_shared()
+        }
+
+        fn unlock_shared(&self) {
+            // SAFETY: only called on behalf of a shared holder.
+            unsafe { self.0.unlock_shared() }
+        }
+
+        fn lock_exclusive(&self) {
+            self.0.lock_exclusive();
+        }
+
+        fn try_lock_exclusive(&self) -> bool {
+            self.0.try_lock_exclusive()
+        }
+
+        fn unlock_exclusive(&self) {
+            // SAFETY: only called on behalf of the exclusive holder.
+            unsafe { self.0.unlock_exclusive() }
+        }
+
+        fn downgrade(&self) {
+            // SAFETY: only called on behalf of the exclusive holder.
+            unsafe { self.0.downgrade() }
+        }
+
+        fn lock_upgradable(&self) {
+            self.0.lock_upgradable();
+        }
+
+        fn try_lock_upgradable(&self) -> bool {
+            self.0.try_lock_upgradable()
+        }
+
+        fn unlock_upgradable(&self) {
+            // SAFETY: only called on behalf of the upgradable holder.
+            unsafe { self.0.unlock_upgradable() }
+        }
+
+        fn upgrade(&self) {
+            // SAFETY: only called on behalf of the upgradable holder.
+            unsafe { self.0.upgrade() }
+        }
+
+        fn try_upgrade(&self) -> bool {
+            // SAFETY: only called on behalf of the upgradable holder.
+            unsafe { self.0.try_upgrade() }
+        }
+
+        fn downgrade_upgradable(&self) {
+            // SAFETY: only called on behalf of the upgradable holder.
+            unsafe { self.0.downgrade_upgradable() }
+        }
+
+        fn lock_shared_until(&self, deadline: Instant) -> bool {
+            self.0.try_lock_shared_until(deadline)
+        }
+
+        fn lock_exclusive_until(&self, deadline: Instant) -> bool {
+            self.0.try_lock_exclusive_until(deadline)
+        }
+    }
+}
+
+/// Backend that serializes every access through a `std::sync::Mutex`.
+///
+/// Always compiled, since `SharedMutex` is built on it.
+mod mutex {
+    use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+    use std::time::Instant;
+
+    use super::LockBackend;
+
+    /// Backend that treats every lock mode as exclusive, built on
+    /// `std::sync::Mutex` and `Condvar`.
+    ///
+    /// Readers take turns instead of running concurrently, so a thread that holds a
+    /// guard deadlocks if it locks the same container again. In exchange the lock
+    /// state is a single flag.
+    ///
+    /// `SharedMutex` always uses it internally, but it is only exported for use with
+    /// `Shared<T, B>` with the `backend-mutex` feature flag.
+    #[derive(Debug, Default)]
+    pub struct MutexBackend {
+        locked: Mutex<bool>,
+        unlocked: Condvar,
+    }
+
+    impl MutexBackend {
+        fn locked(&self) -> MutexGuard<'_, bool> {
+            // Never held across user code, so poisoning carries no information.
+            self.locked.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        fn lock_until(&self, deadline: Option<Instant>) -> bool {
+            let mut locked = self.locked();
+            while *locked {
+                locked = match deadline {
+                    None => self
+                        .unlocked
+                        .wait(locked)
+                        .unwrap_or_else(PoisonError::into_inner),
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            return false;
+                        }
+                        self.unlocked
+                            .wait_timeout(locked, deadline - now)
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .0
+                    }
+                };
+            }
+            *locked = true;
+            true
+        }
+
+        fn try_lock(&self) -> bool {
+            !std::mem::replace(&mut *self.locked(), true)
+        }
+
+        fn unlock(&self) {
+            *self.locked() = false;
+            self.unlocked.notify_one();
+        }
+    }
+
+    // SAFETY: at most one holder of any mode exists at a time.
+    unsafe impl LockBackend for MutexBackend {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn lock_shared(&self) {
+            self.lock_until(None);
+        }
+
+        fn try_lock_shared(&self) -> bool {
+            self.try_lock()
+        }
+
+        fn unlock_shared(&self) {
+            self.unlock();
+        }
+
+        fn lock_exclusive(&self) {
+            self.lock_until(None);
+        }
+
+        fn try_lock_exclusive(&self) -> bool {
+            self.try_lock()
+        }
+
+        fn unlock_exclusive(&self) {
+            self.unlock();
+        }
+
+        fn downgrade(&self) {}
+
+        fn lock_upgradable(&self) {
+            self.lock_until(None);
+        }
+
+        fn try_lock_upgradable(&self) -> bool {
+            self.try_lock()
+        }
+
+        fn unlock_upgradable(&self) {
+            self.unlock();
+        }
+
+        fn upgrade(&self) {}
+
+        fn try_upgrade(&self) -> bool {
+            true
+        }
+
+        fn downgrade_upgradable(&self) {}
+
+        fn lock_shared_until(&self, deadline: Instant) -> bool {
+            self.lock_until(Some(deadline))
+        }
+
+        fn lock_exclusive_until(&self, deadline: Instant) -> bool {
+            self.lock_until(Some(deadline))
+        }
+    }
+}
+
+/// Backend that spins on a single atomic word.
+#[cfg(feature = "backend-spin")]
+mod spin {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::LockBackend;
+
+    const WRITER: usize = 1;
+    const UPGRADABLE: usize = 1 << 1;
+    const READER: usize = 1 << 2;
+
+    /// Backend that busy-waits instead of parking threads.
+    ///
+    /// The whole lock is one word and acquiring it never makes a system call, which
+    /// suits short critical sections. Long waits burn CPU, and waiting writers do
+    /// not hold back new readers. Only available with the `backend-spin` feature
+    /// flag.
+    #[derive(Debug, Default)]
+    pub struct SpinBackend {
+        /// Reader count in units of `READER`, plus the `WRITER` and `UPGRADABLE`
+        /// flags.
+        state: AtomicUsize,
+    }
+
+    fn spin(mut attempt: impl FnMut() -> bool) {
+        while !attempt() {
+            std::hint::spin_loop();
+        }
+    }
+
+    // SAFETY: readers are refused while `WRITER` is set, and `WRITER` is only set
+    // when no reader, writer or upgradable holder exists.
+    unsafe impl LockBackend for SpinBackend {
+        fn new() -> Self {
+            Self::default()
+        }
+
+        fn lock_shared(&self) {
+            spin(|| self.try_lock_shared());
+        }
+
+        fn try_lock_shared(&self) -> bool {
+            let state = self.state.fetch_add(READER, Ordering::Acquire);
+            if state & WRITER != 0 {
+                self.state.fetch_sub(READER, Ordering::Release);
+                return false;
+            }
+            true
+        }
+
+        fn unlock_shared(&self) {
+            self.state.fetch_sub(READER, Ordering::Release);
+        }
+
+        fn lock_exclusive(&self) {
+            spin(|| self.try_lock_exclusive());
+        }
+
+        fn try_lock_exclusive(&self) -> bool {
+            self.state
+                .compare_exchange(0, WRITER, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        }
+
+        fn unlock_exclusive(&self) {
+            self.state.fetch_and(!WRITER, Ordering::Release);
+        }
+
+        fn downgrade(&self) {
+            self.state.fetch_add(READER, Ordering::Acquire);
+            self.state.fetch_and(!WRITER, Ordering::Release);
+        }
+
+        fn lock_upgradable(&self) {
+            spin(|| self.try_lock_upgradable());
+        }
+
+        fn try_lock_upgradable(&self) -> bool {
+            let mut state = self.state.load(Ordering::Relaxed);
+            loop {
+                if state & (WRITER | UPGRADABLE) != 0 {
+                    return false;
+                }
+                match self.state.compare_exchange_weak(
+                    state,
+                    state | UPGRADABLE,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return true,
+                    Err(current) => state = current,
+                }
+            }
+        }
+
+        fn unlock_upgradable(&self) {
+            self.state.fetch_and(!UPGRADABLE, Ordering::Release);
+        }
+
+        fn upgrade(&self) {
+            spin(|| self.try_upgrade());
+        }
+
+        fn try_upgrade(&self) -> bool {
+            self.state
+                .compare_exchange(UPGRADABLE, WRITER, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        }
+
+        fn downgrade_upgradable(&self) {
+            self.state.fetch_add(READER, Ordering::Acquire);
+            self.state.fetch_and(!UPGRADABLE, Ordering::Release);
+        }
+    }
+}
+
+/// Async reader-writer lock for a single thread.
+mod local_async {
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+
+    /// A task waiting for the lock.
+    struct Waiter {
+        id: u64,
+        exclusive: bool,
+        waker: Waker,
+    }
+
+    /// The async counterpart of the wasm32 borrow flag.
+    ///
+    /// Tasks that cannot take the lock queue up and are served in order, so a
+    /// waiting writer is not starved by a stream of new readers.
+    #[derive(Default)]
+    pub(crate) struct LocalAsyncLock {
+        /// Positive: number of readers. Negative: write-locked.
+        borrow: Cell<isize>,
+        queue: RefCell<VecDeque<Waiter>>,
+        next_id: Cell<u64>,
+    }
+
+    impl LocalAsyncLock {
+        pub(crate) fn lock_shared(&self) -> Acquire<'_> {
+            Acquire {
+                lock: self,
+                exclusive: false,
+                queued: None,
+            }
+        }
+
+        pub(crate) fn lock_exclusive(&self) -> Acquire<'_> {
+            Acquire {
+                lock: self,
+                exclusive: true,
+                queued: None,
+            }
+        }
+
+        /// Takes a shared lock unless it is write-locked or tasks are queued.
+        pub(crate) fn try_lock_shared(&self) -> bool {
+            self.try_lock(false)
+        }
+
+        /// Takes the exclusive lock unless it is held or tasks are queued.
+        pub(crate) fn try_lock_exclusive(&self) -> bool {
+            self.try_lock(true)
+        }
+
+        pub(crate) fn unlock_shared(&self) {
+            self.borrow.set(self.borrow.get() - 1);
+            self.wake_front();
+        }
+
+        pub(crate) fn unlock_exclusive(&self) {
+            self.borrow.set(0);
+            self.wake_front();
+        }
+
+        /// Turns the exclusive lock into a shared lock.
+        pub(crate) fn downgrade(&self) {
+            self.borrow.set(1);
+            self.wake_front();
+        }
+
+        fn try_lock(&self, exclusive: bool) -> bool {
+            if !self.queue.borrow().is_empty() || !self.available(exclusive) {
+                return false;
+            }
+            self.take(exclusive);
+            true
+        }
+
+        fn available(&self, exclusive: bool) -> bool {
+            if exclusive {
+                self.borrow.get() == 0
+            } else {
+                self.borrow.get() >= 0
+            }
+        }
+
+        fn take(&self, exclusive: bool) {
+            self.borrow
+                .set(if exclusive { -1 } else { self.borrow.get() + 1 });
+        }
+
+        /// Wakes the first queued task if it can take the lock now.
+        fn wake_front(&self) {
+            let waker = match self.queue.borrow().front() {
+                Some(front) if self.available(front.exclusive) => front.waker.clone(),
+                _ => return,
+            };
+            // Woken outside the queue borrow, in case the waker polls right away.
+            waker.wake();
+        }
+    }
+
+    /// Future that resolves once the caller holds the lock.
+    ///
+    /// Dropping it before then gives up its place in the queue.
+    pub(crate) struct Acquire<'a> {
+        lock: &'a LocalAsyncLock,
+        exclusive: bool,
+        /// Our place in the queue, once we had to wait.
+        queued: Option<u64>,
+    }
+
+    impl Future for Acquire<'_> {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            let lock = self.lock;
+            let exclusive = self.exclusive;
+            let Some(id) = self.queued else {
+                if lock.try_lock(exclusive) {
+                    return Poll::Ready(());
+                }
+                let id = lock.next_id.get();
+                lock.next_id.set(id + 1);
+                lock.queue.borrow_mut().push_back(Waiter {
+                    id,
+                    exclusive,
+                    waker: cx.waker().clone(),
+                });
+                self.queued = Some(id);
+                return Poll::Pending;
+            };
+
+            let mut queue = lock.queue.borrow_mut();
+            if queue.front().is_some_and(|front| front.id == id) && lock.available(exclusive) {
+                queue.pop_front();
+                drop(queue);
+                lock.take(exclusive);
+                self.queued = None;
+                // Readers queued right behind us may share the lock.
+                lock.wake_front();
+                return Poll::Ready(());
+            }
+            if let Some(waiter) = queue.iter_mut().find(|waiter| waiter.id == id) {
+                waiter.waker.clone_from(cx.waker());
+            }
+            Poll::Pending
+        }
+    }
+
+    impl Drop for Acquire<'_> {
+        fn drop(&mut self) {
+            if let Some(id) = self.queued {
+                self.lock
+                    .queue
+                    .borrow_mut()
+                    .retain(|waiter| waiter.id != id);
+                // The task behind us may be able to go ahead now.
+                self.lock.wake_front();
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@
 #[cfg(test)]
 mod shared_sync_tests {
     use shared_container::{Shared, SyncAccess};
+    use std::sync::{Arc, Mutex};
 
     #[derive(Debug, Clone, PartialEq)]
     struct TestData {
@@ -121,107 +122,2581 @@ mod shared_sync_tests {
 
         let err = AccessError::Poisoned;
         assert_eq!(err.to_string(), "lock poisoned by panic");
+
+        let err = AccessError::WouldBlock;
+        assert_eq!(err.to_string(), "lock is busy: operation would block");
+
+        let err = AccessError::Timeout;
+        assert_eq!(err.to_string(), "timed out waiting for lock");
+
+        let err = AccessError::WrongThread;
+        assert_eq!(
+            err.to_string(),
+            "container accessed from a thread other than its owner"
+        );
+
+        let err = AccessError::BlockingInRuntime;
+        assert_eq!(
+            err.to_string(),
+            "blocking access attempted from inside an async runtime"
+        );
+
+        let err = AccessError::NotUnique;
+        assert_eq!(err.to_string(), "container has other handles");
+    }
+
+    #[test]
+    fn test_sync_access_is_dyn_compatible() {
+        use shared_container::{AccessError, SyncReadGuard, SyncWriteGuard};
+
+        let containers: Vec<Box<dyn SyncAccess<i32>>> =
+            vec![Box::new(Shared::new(1)), Box::new(shared_container::SharedMutex::new(2))];
+        let values: Vec<i32> = containers.iter().map(|c| *c.read().unwrap()).collect();
+        assert_eq!(values, [1, 2]);
+
+        // An implementor written before the newer methods existed still compiles
+        struct Legacy(Shared<i32>);
+
+        impl SyncAccess<i32> for Legacy {
+            fn read(&self) -> Result<SyncReadGuard<'_, i32>, AccessError> {
+                self.0.read()
+            }
+
+            fn write(&self) -> Result<SyncWriteGuard<'_, i32>, AccessError> {
+                self.0.write()
+            }
+
+            fn get_cloned(&self) -> Result<i32, AccessError> {
+                self.0.get_cloned()
+            }
+        }
+
+        let legacy = Legacy(Shared::new(3));
+        assert_eq!(legacy.try_read().unwrap_err(), AccessError::UnsupportedMode);
+        assert_eq!(legacy.swap(&Legacy(Shared::new(4))), Err(AccessError::UnsupportedMode));
+        assert_eq!(legacy.replace(5).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_shared_try_read_write() {
+        use shared_container::AccessError;
+
+        let container = Shared::new(TestData { value: 42 });
+
+        {
+            let mut guard = container.try_write().unwrap();
+            guard.value = 100;
+
+            // Writer held: both try methods fail fast
+            assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+            assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        }
+
+        let guard1 = container.try_read().unwrap();
+        let guard2 = container.try_read().unwrap();
+        assert_eq!(guard1.value, 100);
+        assert_eq!(guard2.value, 100);
+
+        // Readers held: writer fails fast
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+    }
+
+    #[test]
+    fn test_shared_try_write_from_other_thread() {
+        use shared_container::AccessError;
+
+        let container = Shared::new(TestData { value: 42 });
+        let guard = container.read().unwrap();
+
+        let other = container.clone();
+        let result = std::thread::spawn(move || other.try_write().map(|_| ()))
+            .join()
+            .unwrap();
+        assert_eq!(result.unwrap_err(), AccessError::WouldBlock);
+
+        drop(guard);
+        assert!(container.try_write().is_ok());
+    }
+
+    #[test]
+    fn test_shared_timeout_expires() {
+        use shared_container::AccessError;
+        use std::time::{Duration, Instant};
+
+        let container = Shared::new(TestData { value: 42 });
+        let writer = container.write().unwrap();
+
+        let other = container.clone();
+        let (read, write) = std::thread::spawn(move || {
+            let read = other.read_timeout(Duration::from_millis(20)).map(|_| ());
+            let deadline = Instant::now() + Duration::from_millis(20);
+            let write = other.write_until(deadline).map(|_| ());
+            (read, write)
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(read.unwrap_err(), AccessError::Timeout);
+        assert_eq!(write.unwrap_err(), AccessError::Timeout);
+
+        // A timed-out writer must not leave readers queued behind it
+        drop(writer);
+        let reader = container.read().unwrap();
+        assert_eq!(
+            container
+                .write_timeout(Duration::from_millis(10))
+                .unwrap_err(),
+            AccessError::Timeout
+        );
+        assert_eq!(
+            container.read_timeout(Duration::ZERO).unwrap().value,
+            reader.value
+        );
+    }
+
+    #[test]
+    fn test_shared_timeout_acquires_after_release() {
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let container = Shared::new(TestData { value: 42 });
+        let (locked_tx, locked_rx) = mpsc::channel();
+
+        let other = container.clone();
+        let writer = std::thread::spawn(move || {
+            let mut guard = other.write().unwrap();
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+            guard.value = 100;
+        });
+
+        locked_rx.recv().unwrap();
+        let guard = container.read_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(guard.value, 100);
+        drop(guard);
+        writer.join().unwrap();
+
+        let mut guard = container.write_timeout(Duration::from_secs(10)).unwrap();
+        guard.value = 7;
+        drop(guard);
+        assert_eq!(container.get_cloned().unwrap().value, 7);
+    }
+
+    #[test]
+    fn test_shared_closure_access() {
+        let container = Shared::new(TestData { value: 42 });
+
+        assert_eq!(container.with(|d| d.value * 2).unwrap(), 84);
+
+        let old = container
+            .with_mut(|d| std::mem::replace(&mut d.value, 1))
+            .unwrap();
+        assert_eq!(old, 42);
+
+        container
+            .update(|d| TestData {
+                value: d.value + 10,
+            })
+            .unwrap();
+        assert_eq!(container.get_cloned().unwrap().value, 11);
+
+        let old = container.replace(TestData { value: 5 }).unwrap();
+        assert_eq!(old.value, 11);
+
+        // No guard outlives the calls above
+        assert!(container.try_write().is_ok());
+    }
+
+    #[test]
+    fn test_shared_take() {
+        let container = Shared::new(vec![1, 2, 3]);
+        assert_eq!(container.take().unwrap(), vec![1, 2, 3]);
+        assert!(container.get_cloned().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_shared_swap() {
+        let a = Shared::new(TestData { value: 1 });
+        let b = Shared::new(TestData { value: 2 });
+
+        a.swap(&b).unwrap();
+        assert_eq!(a.get_cloned().unwrap().value, 2);
+        assert_eq!(b.get_cloned().unwrap().value, 1);
+
+        // Swapping with itself or an alias must not deadlock
+        a.swap(&a).unwrap();
+        a.swap(&a.clone()).unwrap();
+        assert_eq!(a.get_cloned().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_shared_swap_opposite_order_no_deadlock() {
+        let a = Shared::new(0);
+        let b = Shared::new(1);
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let (a, b) = (a.clone(), b.clone());
+                std::thread::spawn(move || {
+                    for _ in 0..500 {
+                        if i % 2 == 0 {
+                            a.swap(&b).unwrap();
+                        } else {
+                            b.swap(&a).unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut values = [a.get_cloned().unwrap(), b.get_cloned().unwrap()];
+        values.sort();
+        assert_eq!(values, [0, 1]);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Config {
+        name: String,
+        db: TestData,
+        tags: Vec<String>,
+    }
+
+    fn config() -> Shared<Config> {
+        Shared::new(Config {
+            name: "service".to_string(),
+            db: TestData { value: 5432 },
+            tags: vec!["a".to_string()],
+        })
+    }
+
+    #[test]
+    fn test_read_guard_map() {
+        use shared_container::{AccessError, SyncReadGuard};
+
+        let container = config();
+        let db = SyncReadGuard::map(container.read().unwrap(), |c| &c.db);
+        assert_eq!(db.value, 5432);
+
+        let port = SyncReadGuard::map(db, |d| &d.value);
+        assert_eq!(*port, 5432);
+
+        // The lock is still held by the mapped guard
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        drop(port);
+        assert!(container.try_write().is_ok());
+    }
+
+    #[test]
+    fn test_read_guard_map_returned_from_function() {
+        use shared_container::SyncReadGuard;
+
+        fn name(container: &Shared<Config>) -> SyncReadGuard<'_, str> {
+            SyncReadGuard::map(container.read().unwrap(), |c| c.name.as_str())
+        }
+
+        let container = config();
+        assert_eq!(&*name(&container), "service");
+    }
+
+    #[test]
+    fn test_read_guard_try_map() {
+        use shared_container::SyncReadGuard;
+
+        let container = config();
+
+        let tag = SyncReadGuard::try_map(container.read().unwrap(), |c| c.tags.first());
+        assert_eq!(tag.unwrap().as_str(), "a");
+
+        let missing = SyncReadGuard::try_map(container.read().unwrap(), |c| c.tags.get(5));
+        let original = missing.unwrap_err();
+        assert_eq!(original.name, "service");
+    }
+
+    #[test]
+    fn test_read_guard_map_split() {
+        use shared_container::{AccessError, SyncReadGuard};
+
+        let container = config();
+        let (name, db) = SyncReadGuard::map_split(container.read().unwrap(), |c| (&c.name, &c.db));
+        assert_eq!(name.as_str(), "service");
+        assert_eq!(db.value, 5432);
+
+        drop(name);
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        drop(db);
+        assert!(container.try_write().is_ok());
+    }
+
+    #[test]
+    fn test_write_guard_map() {
+        use shared_container::{AccessError, SyncMappedWriteGuard, SyncWriteGuard};
+
+        let container = config();
+        let mut db = SyncWriteGuard::map(container.write().unwrap(), |c| &mut c.db);
+        db.value = 6543;
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+
+        let mut port = SyncMappedWriteGuard::map(db, |d| &mut d.value);
+        *port += 1;
+        drop(port);
+
+        assert_eq!(container.get_cloned().unwrap().db.value, 6544);
+    }
+
+    #[test]
+    fn test_write_guard_try_map() {
+        use shared_container::SyncWriteGuard;
+
+        let container = config();
+
+        let tag = SyncWriteGuard::try_map(container.write().unwrap(), |c| c.tags.first_mut());
+        tag.unwrap().push('b');
+
+        let missing = SyncWriteGuard::try_map(container.write().unwrap(), |c| c.tags.get_mut(5));
+        let mut original = missing.unwrap_err();
+        original.name.push('!');
+        drop(original);
+
+        let snapshot = container.get_cloned().unwrap();
+        assert_eq!(snapshot.tags, vec!["ab".to_string()]);
+        assert_eq!(snapshot.name, "service!");
+    }
+
+    #[test]
+    fn test_write_guard_map_split() {
+        use shared_container::{AccessError, SyncWriteGuard};
+
+        let container = config();
+        let (mut name, mut db) =
+            SyncWriteGuard::map_split(container.write().unwrap(), |c| (&mut c.name, &mut c.db));
+        name.push_str("-v2");
+        db.value = 1;
+
+        drop(name);
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(db);
+
+        let snapshot = container.get_cloned().unwrap();
+        assert_eq!(snapshot.name, "service-v2");
+        assert_eq!(snapshot.db.value, 1);
+    }
+
+    #[test]
+    fn test_mapped_write_guard_poisons_on_panic() {
+        use shared_container::{AccessError, SyncWriteGuard};
+
+        let container = config();
+        let other = container.clone();
+        let result = std::thread::spawn(move || {
+            let _db = SyncWriteGuard::map(other.write().unwrap(), |c| &mut c.db);
+            panic!("writer panicked");
+        })
+        .join();
+        assert!(result.is_err());
+
+        assert_eq!(container.read().unwrap_err(), AccessError::Poisoned);
+    }
+
+    #[test]
+    fn test_upgradable_read_coexists_with_readers() {
+        use shared_container::AccessError;
+
+        let container = Shared::new(TestData { value: 1 });
+        let reader = container.read().unwrap();
+        let upgradable = container.upgradable_read().unwrap();
+        assert_eq!(reader.value, 1);
+        assert_eq!(upgradable.value, 1);
+
+        assert!(container.try_read().is_ok());
+        assert_eq!(
+            container.try_upgradable_read().unwrap_err(),
+            AccessError::WouldBlock
+        );
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+
+        drop(reader);
+        drop(upgradable);
+        assert!(container.try_write().is_ok());
+    }
+
+    #[test]
+    fn test_upgradable_read_check_then_insert() {
+        use shared_container::SyncUpgradableReadGuard;
+        use std::collections::HashMap;
+
+        let cache = Shared::new(HashMap::new());
+        for _ in 0..2 {
+            let entry = cache.upgradable_read().unwrap();
+            if !entry.contains_key("answer") {
+                let mut entry = SyncUpgradableReadGuard::upgrade(entry).unwrap();
+                let previous = entry.insert("answer", 42);
+                assert!(previous.is_none());
+            }
+        }
+        assert_eq!(cache.get_cloned().unwrap()["answer"], 42);
+    }
+
+    #[test]
+    fn test_upgradable_try_upgrade_and_downgrade() {
+        use shared_container::{AccessError, SyncUpgradableReadGuard};
+
+        let container = Shared::new(TestData { value: 1 });
+        let reader = container.read().unwrap();
+        let upgradable = container.upgradable_read().unwrap();
+
+        // A plain reader is still active
+        let upgradable = SyncUpgradableReadGuard::try_upgrade(upgradable).unwrap_err();
+        drop(reader);
+        let mut writer = SyncUpgradableReadGuard::try_upgrade(upgradable).unwrap();
+        writer.value = 2;
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(writer);
+
+        let upgradable = container.upgradable_read().unwrap();
+        let reader = SyncUpgradableReadGuard::downgrade(upgradable);
+        assert_eq!(reader.value, 2);
+        assert!(container.try_upgradable_read().is_ok());
+    }
+
+    #[test]
+    fn test_upgrade_waits_for_readers() {
+        use shared_container::SyncUpgradableReadGuard;
+        use std::sync::mpsc;
+        use std::time::Duration;
+
+        let container = Shared::new(TestData { value: 0 });
+        let other = container.clone();
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let reader = std::thread::spawn(move || {
+            let guard = other.read().unwrap();
+            locked_tx.send(()).unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+            guard.value
+        });
+
+        locked_rx.recv().unwrap();
+        let upgradable = container.upgradable_read().unwrap();
+        let mut writer = SyncUpgradableReadGuard::upgrade(upgradable).unwrap();
+        writer.value = 1;
+        drop(writer);
+
+        // The reader saw the value from before the upgrade
+        assert_eq!(reader.join().unwrap(), 0);
+        assert_eq!(container.read().unwrap().value, 1);
+    }
+
+    #[test]
+    fn test_upgrade_is_not_overtaken_by_waiting_writer() {
+        use shared_container::SyncUpgradableReadGuard;
+        use std::time::Duration;
+
+        let container = Shared::new(TestData { value: 0 });
+        let upgradable = container.upgradable_read().unwrap();
+
+        let other = container.clone();
+        let writer = std::thread::spawn(move || {
+            let mut guard = other.write().unwrap();
+            guard.value = guard.value * 10 + 2;
+        });
+        std::thread::sleep(Duration::from_millis(20));
+
+        let mut guard = SyncUpgradableReadGuard::upgrade(upgradable).unwrap();
+        guard.value = guard.value * 10 + 1;
+        drop(guard);
+        writer.join().unwrap();
+
+        assert_eq!(container.read().unwrap().value, 12);
+    }
+
+    #[test]
+    fn test_write_guard_downgrade() {
+        use shared_container::{AccessError, SyncWriteGuard};
+        use std::time::Duration;
+
+        let container = Shared::new(TestData { value: 0 });
+        let mut guard = container.write().unwrap();
+
+        let other = container.clone();
+        let writer = std::thread::spawn(move || {
+            other.write().unwrap().value = 2;
+        });
+        std::thread::sleep(Duration::from_millis(20));
+
+        guard.value = 1;
+        let guard = SyncWriteGuard::downgrade(guard);
+        std::thread::sleep(Duration::from_millis(20));
+
+        // The waiting writer did not get in between
+        assert_eq!(guard.value, 1);
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        drop(guard);
+
+        writer.join().unwrap();
+        assert_eq!(container.read().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_owned_read_guard_outlives_container() {
+        use shared_container::OwnedSyncReadGuard;
+
+        fn load() -> OwnedSyncReadGuard<TestData> {
+            Shared::new(TestData { value: 7 }).read_owned().unwrap()
+        }
+
+        let guard = load();
+        assert_eq!(guard.value, 7);
+
+        let container = OwnedSyncReadGuard::container(&guard).clone();
+        assert!(container.try_read().is_ok());
+        assert!(container.try_write().is_err());
+        drop(guard);
+        assert!(container.try_write().is_ok());
+    }
+
+    #[test]
+    fn test_owned_guards_move_across_threads() {
+        use shared_container::AccessError;
+
+        let container = Shared::new(TestData { value: 1 });
+        let mut guard = container.write_owned().unwrap();
+        let handle = std::thread::spawn(move || {
+            guard.value = 2;
+        });
+        handle.join().unwrap();
+        assert_eq!(container.read().unwrap().value, 2);
+
+        let guard = container.read_owned().unwrap();
+        let callback: Box<dyn FnOnce() -> i32 + Send> = Box::new(move || guard.value);
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        assert_eq!(callback(), 2);
+        assert!(container.try_write().is_ok());
+    }
+
+    #[test]
+    fn test_owned_guard_map() {
+        use shared_container::{
+            AccessError, OwnedSyncMappedWriteGuard, OwnedSyncReadGuard, OwnedSyncWriteGuard,
+        };
+
+        let container = config();
+
+        let name = OwnedSyncReadGuard::map(container.read_owned().unwrap(), |c| c.name.as_str());
+        assert_eq!(&*name, "service");
+        let missing = OwnedSyncReadGuard::try_map(name, |n| n.strip_prefix("x"));
+        assert!(missing.is_err());
+        drop(missing);
+
+        let db = OwnedSyncWriteGuard::map(container.write_owned().unwrap(), |c| &mut c.db);
+        let mut port = OwnedSyncMappedWriteGuard::map(db, |d| &mut d.value);
+        *port += 1;
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(port);
+
+        let (mut name, mut tags) =
+            OwnedSyncWriteGuard::map_split(container.write_owned().unwrap(), |c| {
+                (&mut c.name, &mut c.tags)
+            });
+        name.push_str("-v2");
+        tags.clear();
+        drop(name);
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(tags);
+
+        let snapshot = container.get_cloned().unwrap();
+        assert_eq!(snapshot.name, "service-v2");
+        assert_eq!(snapshot.db.value, 5433);
+        assert!(snapshot.tags.is_empty());
+    }
+
+    #[test]
+    fn test_owned_write_guard_downgrade() {
+        use shared_container::{AccessError, OwnedSyncWriteGuard};
+
+        let container = Shared::new(TestData { value: 0 });
+        let mut guard = container.write_owned().unwrap();
+        guard.value = 3;
+        let guard = OwnedSyncWriteGuard::downgrade(guard);
+        assert_eq!(guard.value, 3);
+        assert!(container.try_read().is_ok());
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+    }
+
+    #[test]
+    fn test_owned_write_guard_poisons_on_panic() {
+        use shared_container::AccessError;
+
+        let container = Shared::new(TestData { value: 0 });
+        let guard = container.write_owned().unwrap();
+        let result = std::thread::spawn(move || {
+            let _guard = guard;
+            panic!("writer panicked");
+        })
+        .join();
+        assert!(result.is_err());
+
+        assert_eq!(container.read_owned().unwrap_err(), AccessError::Poisoned);
+    }
+
+    /// Poisons `container` from a thread named `handler`, returning the line of the
+    /// write lock acquisition.
+    fn poison<T: Send + Sync + 'static>(container: &Shared<T>) -> u32 {
+        let other = container.clone();
+        let (line_tx, line_rx) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("handler".to_string())
+            .spawn(move || {
+                let (line, _guard) = (line!(), other.write().unwrap());
+                line_tx.send(line).unwrap();
+                panic!("handler panicked");
+            })
+            .unwrap()
+            .join()
+            .unwrap_err();
+        line_rx.recv().unwrap()
+    }
+
+    #[test]
+    fn test_recover_guard_from_poison_error() {
+        use shared_container::{AccessError, RecoverableError};
+
+        let container = Shared::new(TestData { value: 1 });
+        poison(&container);
+        assert_eq!(container.read().unwrap_err(), AccessError::Poisoned);
+
+        let guard = match container.read_recoverable() {
+            Err(RecoverableError::Poisoned(err)) => err.into_inner(),
+            other => panic!("expected poisoning, got {:?}", other.map(|g| g.value)),
+        };
+        assert_eq!(guard.value, 1);
+        drop(guard);
+
+        let mut guard = match container.write_recoverable() {
+            Err(RecoverableError::Poisoned(err)) => err.into_inner(),
+            _ => panic!("expected poisoning"),
+        };
+        guard.value = 2;
+        drop(guard);
+
+        // Writing through a recovered guard does not clear the poisoning
+        assert!(container.is_poisoned());
+        container.clear_poison();
+        assert!(!container.is_poisoned());
+        assert!(container.poison_info().is_none());
+        assert_eq!(container.read().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_recoverable_error_converts_to_access_error() {
+        use shared_container::{AccessError, RecoverableError};
+
+        fn read_value(container: &Shared<TestData>) -> Result<i32, AccessError> {
+            Ok(container.read_recoverable()?.value)
+        }
+
+        let container = Shared::new(TestData { value: 1 });
+        assert_eq!(read_value(&container), Ok(1));
+        poison(&container);
+        assert_eq!(read_value(&container), Err(AccessError::Poisoned));
+
+        let err = container.read_recoverable().unwrap_err();
+        assert!(matches!(err, RecoverableError::Poisoned(_)));
+        assert!(err.to_string().starts_with("lock poisoned by panic: "));
+    }
+
+    #[test]
+    fn test_poison_info_records_thread_and_location() {
+        use shared_container::RecoverableError;
+
+        let container = Shared::new(TestData { value: 1 });
+        assert!(container.poison_info().is_none());
+        let line = poison(&container);
+
+        let info = container.poison_info().unwrap();
+        assert_eq!(info.thread_name(), Some("handler"));
+        assert_ne!(info.thread_id(), std::thread::current().id());
+        assert_eq!(info.location().file(), file!());
+        assert_eq!(info.location().line(), line);
+
+        let Err(RecoverableError::Poisoned(err)) = container.write_recoverable() else {
+            panic!("expected poisoning");
+        };
+        assert_eq!(err.info().location(), info.location());
+        let message = err.to_string();
+        assert!(message.contains("thread 'handler' panicked"), "{message}");
+        assert!(message.contains(file!()), "{message}");
+    }
+
+    #[test]
+    fn test_poison_location_tracks_closure_access() {
+        let container = Shared::new(TestData { value: 1 });
+        let other = container.clone();
+        std::thread::spawn(move || {
+            other
+                .with_mut(|data| {
+                    data.value = 2;
+                    panic!("closure panicked");
+                })
+                .unwrap();
+        })
+        .join()
+        .unwrap_err();
+
+        let info = container.poison_info().unwrap();
+        assert_eq!(info.location().file(), file!());
+    }
+
+    #[test]
+    fn test_poison_policy_ignore() {
+        use shared_container::PoisonPolicy;
+
+        let container = Shared::with_poison_policy(TestData { value: 1 }, PoisonPolicy::Ignore);
+        poison(&container);
+
+        assert!(container.is_poisoned());
+        assert_eq!(container.read().unwrap().value, 1);
+        container.write().unwrap().value = 2;
+        assert!(container.read_recoverable().is_ok());
+        assert_eq!(container.get_cloned().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_poison_policy_reset() {
+        use shared_container::PoisonPolicy;
+
+        let container = Shared::with_poison_policy(vec![1, 2], PoisonPolicy::reset_to_default());
+        let other = container.clone();
+        std::thread::spawn(move || {
+            let mut guard = other.write().unwrap();
+            guard.push(3);
+            panic!("writer panicked");
+        })
+        .join()
+        .unwrap_err();
+
+        assert!(!container.is_poisoned());
+        assert!(container.poison_info().is_none());
+        assert!(container.read().unwrap().is_empty());
+
+        let container = Shared::with_poison_policy(
+            TestData { value: 1 },
+            PoisonPolicy::reset_with(|| TestData { value: -1 }),
+        );
+        poison(&container);
+        assert_eq!(container.read().unwrap().value, -1);
+    }
+
+    #[test]
+    fn test_poison_policy_reset_waits_for_split_guards() {
+        use shared_container::{OwnedSyncWriteGuard, PoisonPolicy};
+
+        let container = Shared::with_poison_policy(
+            (TestData { value: 1 }, TestData { value: 2 }),
+            PoisonPolicy::reset_with(|| (TestData { value: 0 }, TestData { value: 0 })),
+        );
+        let (first, mut second) =
+            OwnedSyncWriteGuard::map_split(container.write_owned().unwrap(), |pair| {
+                (&mut pair.0, &mut pair.1)
+            });
+        std::thread::spawn(move || {
+            let _first = first;
+            panic!("writer panicked");
+        })
+        .join()
+        .unwrap_err();
+
+        // The other half still holds the lock and sees the original value
+        second.value += 10;
+        assert_eq!(second.value, 12);
+        drop(second);
+
+        let pair = container.get_cloned().unwrap();
+        assert_eq!((pair.0.value, pair.1.value), (0, 0));
+    }
+
+    /// Subscribes to `container`, recording every value the observer sees.
+    fn record<T: Clone + Send + Sync + 'static>(
+        container: &Shared<T>,
+    ) -> (shared_container::Subscription, Arc<Mutex<Vec<T>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let subscription = container.subscribe(move |value: &T| {
+            log.lock().unwrap().push(value.clone());
+        });
+        (subscription, seen)
+    }
+
+    #[test]
+    fn test_subscribe_fires_after_write_guard_release() {
+        let container = Shared::new(1);
+        let (subscription, seen) = record(&container);
+
+        let mut guard = container.write().unwrap();
+        *guard = 2;
+        *guard = 3;
+        assert!(seen.lock().unwrap().is_empty());
+        drop(guard);
+        assert_eq!(*seen.lock().unwrap(), [3]);
+
+        container.with_mut(|v| *v += 1).unwrap();
+        container.replace(10).unwrap();
+        drop(container.read().unwrap());
+        assert_eq!(*seen.lock().unwrap(), [3, 4, 10]);
+
+        drop(subscription);
+        container.replace(11).unwrap();
+        assert_eq!(*seen.lock().unwrap(), [3, 4, 10]);
+    }
+
+    #[test]
+    fn test_subscribe_fires_once_for_split_and_owned_guards() {
+        use shared_container::{OwnedSyncWriteGuard, SyncWriteGuard};
+
+        let container = Shared::new((1, 2));
+        let (_subscription, seen) = record(&container);
+
+        let (mut first, mut second) =
+            SyncWriteGuard::map_split(container.write().unwrap(), |p| (&mut p.0, &mut p.1));
+        *first = 10;
+        drop(first);
+        *second = 20;
+        assert!(seen.lock().unwrap().is_empty());
+        drop(second);
+        assert_eq!(*seen.lock().unwrap(), [(10, 20)]);
+
+        let mut owned = OwnedSyncWriteGuard::map(container.write_owned().unwrap(), |p| &mut p.0);
+        *owned = 30;
+        drop(owned);
+        assert_eq!(*seen.lock().unwrap(), [(10, 20), (30, 20)]);
+    }
+
+    #[test]
+    fn test_subscribe_fires_on_downgrade() {
+        use shared_container::SyncWriteGuard;
+
+        let container = Shared::new(1);
+        let (_subscription, seen) = record(&container);
+
+        let mut guard = container.write().unwrap();
+        *guard = 2;
+        let guard = SyncWriteGuard::downgrade(guard);
+        assert_eq!(*seen.lock().unwrap(), [2]);
+        drop(guard);
+        assert_eq!(*seen.lock().unwrap(), [2]);
+    }
+
+    #[test]
+    fn test_subscribe_from_other_thread_writes() {
+        let container = Shared::new(0);
+        let (subscription, seen) = record(&container);
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let container = container.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        container.with_mut(|v| *v += 1).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Every write was observed, in order
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(seen, (1..=40).collect::<Vec<_>>());
+
+        // Subscriptions can be moved to other threads
+        std::thread::spawn(move || drop(subscription))
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_subscribe_changes_skips_unchanged_values() {
+        let container = Shared::new(1);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let _subscription = container
+            .subscribe_changes(move |value: &i32| log.lock().unwrap().push(*value))
+            .unwrap();
+
+        container.replace(1).unwrap();
+        container.replace(2).unwrap();
+        container.replace(2).unwrap();
+        container.with_mut(|_| ()).unwrap();
+        container.replace(1).unwrap();
+        assert_eq!(*seen.lock().unwrap(), [2, 1]);
+    }
+
+    #[test]
+    fn test_panicking_observer_releases_lock() {
+        let container = Shared::new(1);
+        let subscription = container.subscribe(|value: &i32| {
+            if *value == 2 {
+                panic!("observer panicked");
+            }
+        });
+
+        let result = std::panic::catch_unwind(|| container.replace(2).unwrap());
+        assert!(result.is_err());
+
+        // The write itself completed before the observer ran
+        assert!(!container.is_poisoned());
+        assert_eq!(*container.try_read().unwrap(), 2);
+        drop(subscription);
+        assert!(container.try_write().is_ok());
+    }
+
+    #[test]
+    fn test_subscription_outlives_container() {
+        let container = Shared::new(1);
+        let (subscription, seen) = record(&container);
+        container.replace(2).unwrap();
+        drop(container);
+        drop(subscription);
+        assert_eq!(*seen.lock().unwrap(), [2]);
+    }
+
+    #[test]
+    fn test_wait_until_blocks_until_predicate_holds() {
+        use shared_container::AccessError;
+
+        let container = Shared::new(TestData { value: 0 });
+        let waiters: Vec<_> = (0..4)
+            .map(|_| {
+                let container = container.clone();
+                std::thread::spawn(move || {
+                    let guard = container.wait_until(|d| d.value >= 3).unwrap();
+                    guard.value
+                })
+            })
+            .collect();
+
+        for _ in 0..3 {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            container.with_mut(|d| d.value += 1).unwrap();
+        }
+        for waiter in waiters {
+            assert_eq!(waiter.join().unwrap(), 3);
+        }
+
+        // The returned guard keeps writers out while the caller inspects the value
+        let guard = container.wait_until(|d| d.value == 3).unwrap();
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        drop(guard);
+    }
+
+    #[test]
+    fn test_wait_until_timeout() {
+        use shared_container::AccessError;
+        use std::time::Duration;
+
+        let container = Shared::new(TestData { value: 0 });
+
+        // Already satisfied, so no waiting is needed
+        let guard = container
+            .wait_until_timeout(Duration::ZERO, |d| d.value == 0)
+            .unwrap();
+        drop(guard);
+
+        // Writes that do not satisfy the predicate do not end the wait
+        let writer = container.clone();
+        let handle = std::thread::spawn(move || {
+            for value in 1..=5 {
+                writer.replace(TestData { value }).unwrap();
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        });
+        let result = container.wait_until_timeout(Duration::from_millis(100), |d| d.value < 0);
+        assert_eq!(result.unwrap_err(), AccessError::Timeout);
+        handle.join().unwrap();
+
+        let writer = container.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            writer.replace(TestData { value: -1 }).unwrap();
+        });
+        let guard = container
+            .wait_until_timeout(Duration::from_secs(10), |d| d.value < 0)
+            .unwrap();
+        assert_eq!(guard.value, -1);
+        drop(guard);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_wait_until_reports_poisoning() {
+        use shared_container::AccessError;
+
+        let container = Shared::new(TestData { value: 0 });
+        let waiter = {
+            let container = container.clone();
+            std::thread::spawn(move || container.wait_until(|d| d.value > 0).map(|_| ()))
+        };
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        poison(&container);
+        assert_eq!(waiter.join().unwrap(), Err(AccessError::Poisoned));
+    }
+
+    #[test]
+    fn test_shared_poisoned_by_panicking_writer() {
+        use shared_container::AccessError;
+
+        let container = Shared::new(TestData { value: 42 });
+        let other = container.clone();
+        let result = std::thread::spawn(move || {
+            let _guard = other.write().unwrap();
+            panic!("writer panicked");
+        })
+        .join();
+        assert!(result.is_err());
+
+        assert_eq!(container.read().unwrap_err(), AccessError::Poisoned);
+        assert_eq!(container.try_write().unwrap_err(), AccessError::Poisoned);
+    }
+}
+
+#[cfg(test)]
+mod backend_tests {
+    use shared_container::{
+        AccessError, DefaultBackend, LockBackend, OwnedSyncWriteGuard, Shared, SyncAccess,
+        SyncUpgradableReadGuard, SyncWriteGuard,
+    };
+    use std::time::Duration;
+
+    /// Behavior that must not depend on the lock backend.
+    fn exercise<B: LockBackend>() {
+        let container = Shared::<Vec<i32>, B>::with_backend(vec![1, 2]);
+        container.write().unwrap().push(3);
+        assert_eq!(container.get_cloned().unwrap(), [1, 2, 3]);
+
+        // The backend lock is released once both halves are gone
+        let (mut head, mut tail) =
+            SyncWriteGuard::map_split(container.write().unwrap(), |v| v.split_at_mut(1));
+        head[0] = 10;
+        tail[0] = 20;
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(head);
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(tail);
+        assert_eq!(*container.try_read().unwrap(), [10, 20, 3]);
+
+        // Upgrade and downgrade
+        let guard = container.upgradable_read().unwrap();
+        let mut guard = SyncUpgradableReadGuard::upgrade(guard).unwrap();
+        guard.push(4);
+        let guard = SyncWriteGuard::downgrade(guard);
+        assert_eq!(*guard, [10, 20, 3, 4]);
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        drop(guard);
+
+        // Owned guards
+        let mut guard = container.write_owned().unwrap();
+        guard.clear();
+        let guard = OwnedSyncWriteGuard::downgrade(guard);
+        assert!(guard.is_empty());
+        drop(guard);
+
+        // Timeouts
+        let guard = container.write().unwrap();
+        let other = container.clone();
+        std::thread::spawn(move || {
+            assert_eq!(
+                other.read_timeout(Duration::from_millis(20)).unwrap_err(),
+                AccessError::Timeout
+            );
+            assert_eq!(
+                other.write_timeout(Duration::from_millis(20)).unwrap_err(),
+                AccessError::Timeout
+            );
+        })
+        .join()
+        .unwrap();
+        drop(guard);
+
+        // Waiting for a write
+        let writer = container.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            writer.write().unwrap().push(7);
+        });
+        let guard = container
+            .wait_until_timeout(Duration::from_secs(10), |v| v.contains(&7))
+            .unwrap();
+        assert_eq!(*guard, [7]);
+        drop(guard);
+        handle.join().unwrap();
+
+        // Poisoning
+        let other = container.clone();
+        std::thread::spawn(move || {
+            let _guard = other.write().unwrap();
+            panic!("writer panicked");
+        })
+        .join()
+        .unwrap_err();
+        assert!(container.is_poisoned());
+        assert_eq!(container.read().unwrap_err(), AccessError::Poisoned);
+        container.clear_poison();
+        assert_eq!(container.get_cloned().unwrap(), [7]);
+    }
+
+    /// Readers share the lock on backends that support it.
+    fn readers_share<B: LockBackend>() {
+        let container = Shared::<i32, B>::with_backend(1);
+        let first = container.read().unwrap();
+        let second = container.try_read().unwrap();
+        let upgradable = container.try_upgradable_read().unwrap();
+        assert_eq!(*first + *second + *upgradable, 3);
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+
+        let upgradable = SyncUpgradableReadGuard::try_upgrade(upgradable).unwrap_err();
+        drop((first, second));
+        let mut guard = SyncUpgradableReadGuard::try_upgrade(upgradable).unwrap();
+        *guard = 2;
+    }
+
+    #[test]
+    fn test_default_backend() {
+        exercise::<DefaultBackend>();
+        readers_share::<DefaultBackend>();
+    }
+
+    #[cfg(feature = "backend-parking-lot")]
+    #[test]
+    fn test_parking_lot_backend() {
+        exercise::<shared_container::ParkingLotBackend>();
+        readers_share::<shared_container::ParkingLotBackend>();
+    }
+
+    #[cfg(feature = "backend-spin")]
+    #[test]
+    fn test_spin_backend() {
+        exercise::<shared_container::SpinBackend>();
+        readers_share::<shared_container::SpinBackend>();
+    }
+
+    #[cfg(feature = "backend-mutex")]
+    #[test]
+    fn test_mutex_backend() {
+        use shared_container::MutexBackend;
+
+        exercise::<MutexBackend>();
+
+        // Readers take turns
+        let container = Shared::<i32, MutexBackend>::with_backend(1);
+        let guard = container.read().unwrap();
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(guard);
+        assert_eq!(*container.try_read().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_backend_type_parameter_defaults() {
+        let container: Shared<String> = Shared::new("default".to_string());
+        let custom: Shared<String, DefaultBackend> = Shared::with_backend("custom".into());
+        assert_eq!(*container.read().unwrap(), "default");
+        assert_eq!(*custom.read().unwrap(), "custom");
+    }
+}
+
+#[cfg(test)]
+mod shared_mutex_tests {
+    use shared_container::{AccessError, SharedMutex, SyncAccess};
+    use std::cell::Cell;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_shared_mutex_accepts_non_sync_payloads() {
+        assert_send_sync::<SharedMutex<Cell<i32>>>();
+        assert_send_sync::<SharedMutex<mpsc::Sender<i32>>>();
+
+        let (tx, rx) = mpsc::channel();
+        let sender = SharedMutex::new(tx);
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let sender = sender.clone();
+                std::thread::spawn(move || sender.lock().unwrap().send(i).unwrap())
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        drop(sender);
+
+        let mut received: Vec<_> = rx.iter().collect();
+        received.sort();
+        assert_eq!(received, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_shared_mutex_lock_is_exclusive() {
+        let counter = SharedMutex::new(Cell::new(0));
+        let guard = counter.lock().unwrap();
+        guard.set(1);
+
+        // Reads take the lock exclusively as well
+        assert_eq!(counter.try_lock().unwrap_err(), AccessError::WouldBlock);
+        assert_eq!(counter.try_read().unwrap_err(), AccessError::WouldBlock);
+
+        let other = counter.clone();
+        let result = std::thread::spawn(move || {
+            other
+                .lock_timeout(Duration::from_millis(10))
+                .map(|guard| guard.get())
+        })
+        .join()
+        .unwrap();
+        assert_eq!(result, Err(AccessError::Timeout));
+
+        drop(guard);
+        assert_eq!(counter.read().unwrap().get(), 1);
+    }
+
+    #[test]
+    fn test_shared_mutex_closure_access() {
+        let list = SharedMutex::new(vec![1, 2]);
+        list.with_mut(|v| v.push(3)).unwrap();
+        assert_eq!(list.with(|v| v.len()).unwrap(), 3);
+        assert_eq!(list.replace(vec![7]).unwrap(), [1, 2, 3]);
+
+        let other = SharedMutex::new(vec![8]);
+        list.swap(&other).unwrap();
+        assert_eq!(list.get_cloned().unwrap(), [8]);
+        assert_eq!(other.take().unwrap(), [7]);
+    }
+
+    #[test]
+    fn test_shared_mutex_weak_reference() {
+        let container = SharedMutex::new(Cell::new(1));
+        let weak = container.downgrade();
+        weak.upgrade().unwrap().lock().unwrap().set(2);
+        assert_eq!(container.lock().unwrap().get(), 2);
+
+        drop(container);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_shared_mutex_poisoning() {
+        let container = SharedMutex::new(0);
+        let other = container.clone();
+        std::thread::spawn(move || {
+            let _guard = other.lock().unwrap();
+            panic!("holder panicked");
+        })
+        .join()
+        .unwrap_err();
+
+        assert!(container.is_poisoned());
+        assert_eq!(container.lock().unwrap_err(), AccessError::Poisoned);
+        container.clear_poison();
+        assert_eq!(*container.lock().unwrap(), 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_shared_mutex() {
+        use shared_container::AsyncSharedMutex;
+        use tokio::runtime::Runtime;
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            assert_send_sync::<AsyncSharedMutex<Cell<i32>>>();
+
+            let counter = AsyncSharedMutex::new(Cell::new(0));
+            let tasks: Vec<_> = (0..4)
+                .map(|_| {
+                    let counter = counter.clone();
+                    tokio::spawn(async move {
+                        let guard = counter.lock().await;
+                        guard.set(guard.get() + 1);
+                    })
+                })
+                .collect();
+            for task in tasks {
+                task.await.unwrap();
+            }
+            assert_eq!(counter.with_lock(|c| c.get()).await, 4);
+
+            let guard = counter.lock().await;
+            assert_eq!(counter.try_lock().unwrap_err(), AccessError::WouldBlock);
+            drop(guard);
+            counter.try_lock().unwrap().set(5);
+
+            let weak = counter.downgrade();
+            assert_eq!(weak.upgrade().unwrap().lock().await.get(), 5);
+            drop(counter);
+            assert!(weak.upgrade().is_none());
+        });
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg(test)]
+mod async_shared_tests {
+    use shared_container::{AsyncShared, AsyncAccess};
+    use tokio::runtime::Runtime;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct TestData {
+        value: i32,
+    }
+
+    #[test]
+    fn test_async_shared_new_and_read() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new(TestData { value: 42 });
+            let guard = container.read_async().await;
+            assert_eq!(guard.value, 42);
+        });
+    }
+
+    #[test]
+    fn test_async_shared_write() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new(TestData { value: 42 });
+
+            {
+                let mut guard = container.write_async().await;
+                guard.value = 100;
+            }
+
+            let guard = container.read_async().await;
+            assert_eq!(guard.value, 100);
+        });
+    }
+
+    #[test]
+    fn test_async_shared_clone() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container1 = AsyncShared::new(TestData { value: 42 });
+            let container2 = container1.clone();
+
+            {
+                let mut guard = container2.write_async().await;
+                guard.value = 100;
+            }
+
+            let guard = container1.read_async().await;
+            assert_eq!(guard.value, 100);
+        });
+    }
+
+    #[test]
+    fn test_async_shared_get_cloned() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new(TestData { value: 42 });
+            let cloned = container.get_cloned_async().await;
+            assert_eq!(cloned, TestData { value: 42 });
+
+            {
+                let mut guard = container.write_async().await;
+                guard.value = 100;
+            }
+
+            // Cloned value should remain unchanged
+            assert_eq!(cloned, TestData { value: 42 });
+
+            let new_clone = container.get_cloned_async().await;
+            assert_eq!(new_clone, TestData { value: 100 });
+        });
+    }
+
+    #[test]
+    fn test_async_shared_weak() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new(TestData { value: 42 });
+            let weak = container.downgrade();
+
+            let upgraded = weak.upgrade().unwrap();
+            {
+                let mut guard = upgraded.write_async().await;
+                guard.value = 100;
+            }
+
+            {
+                let guard = container.read_async().await;
+                assert_eq!(guard.value, 100);
+            }
+
+            drop(container);
+            drop(upgraded);
+
+            assert!(weak.upgrade().is_none());
+        });
+    }
+
+    #[test]
+    fn test_async_shared_try_read_write() {
+        use shared_container::AccessError;
+
+        let container = AsyncShared::new(TestData { value: 42 });
+
+        {
+            let mut guard = container.try_write_async().unwrap();
+            guard.value = 100;
+
+            assert_eq!(
+                container.try_read_async().unwrap_err(),
+                AccessError::WouldBlock
+            );
+            assert_eq!(
+                container.try_write_async().unwrap_err(),
+                AccessError::WouldBlock
+            );
+        }
+
+        let guard = container.try_read_async().unwrap();
+        assert_eq!(guard.value, 100);
+        assert_eq!(
+            container.try_write_async().unwrap_err(),
+            AccessError::WouldBlock
+        );
+    }
+
+    #[test]
+    fn test_async_shared_closure_access() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new(TestData { value: 42 });
+
+            assert_eq!(container.with_async(|d| d.value * 2).await, 84);
+            container.with_mut_async(|d| d.value += 1).await;
+            container
+                .update_async(|d| TestData { value: d.value * 2 })
+                .await;
+            assert_eq!(container.get_cloned_async().await.value, 86);
+
+            let old = container.replace_async(TestData { value: 1 }).await;
+            assert_eq!(old.value, 86);
+
+            let numbers = AsyncShared::new(vec![1, 2]);
+            assert_eq!(numbers.take_async().await, vec![1, 2]);
+            assert!(numbers.get_cloned_async().await.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_async_shared_closure_in_spawned_task() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new(TestData { value: 0 });
+
+            let tasks: Vec<_> = (0..8)
+                .map(|_| {
+                    let container = container.clone();
+                    tokio::spawn(async move {
+                        container.with_mut_async(|d| d.value += 1).await;
+                    })
+                })
+                .collect();
+            for task in tasks {
+                task.await.unwrap();
+            }
+
+            assert_eq!(container.with_async(|d| d.value).await, 8);
+        });
+    }
+
+    #[test]
+    fn test_async_shared_swap() {
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let a = AsyncShared::new(TestData { value: 1 });
+            let b = AsyncShared::new(TestData { value: 2 });
+
+            a.swap_async(&b).await;
+            assert_eq!(a.get_cloned_async().await.value, 2);
+            assert_eq!(b.get_cloned_async().await.value, 1);
+
+            a.swap_async(&a.clone()).await;
+            assert_eq!(a.get_cloned_async().await.value, 2);
+        });
+    }
+
+    #[test]
+    fn test_async_owned_guards_in_spawned_tasks() {
+        use shared_container::AccessError;
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new(TestData { value: 1 });
+
+            let mut guard = container.write_owned_async().await;
+            tokio::spawn(async move {
+                guard.value = 2;
+            })
+            .await
+            .unwrap();
+
+            let guard = container.read_owned_async().await;
+            assert_eq!(
+                container.try_write_async().unwrap_err(),
+                AccessError::WouldBlock
+            );
+            let value = tokio::spawn(async move { guard.value }).await.unwrap();
+            assert_eq!(value, 2);
+            assert!(container.try_write_owned_async().is_ok());
+        });
+    }
+
+    #[test]
+    fn test_async_guard_map() {
+        use shared_container::{
+            AccessError, AsyncMappedWriteGuard, AsyncReadGuard, AsyncWriteGuard,
+        };
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new((TestData { value: 1 }, vec![1, 2]));
+
+            let data = AsyncReadGuard::map(container.read_async().await, |c| &c.0);
+            assert_eq!(data.value, 1);
+            drop(data);
+
+            let missing = AsyncReadGuard::try_map(container.read_async().await, |c| c.1.get(5));
+            assert_eq!(missing.unwrap_err().1.len(), 2);
+
+            let data = AsyncWriteGuard::map(container.write_async().await, |c| &mut c.0);
+            let mut value = AsyncMappedWriteGuard::map(data, |d| &mut d.value);
+            *value = 3;
+            assert_eq!(
+                container.try_read_async().unwrap_err(),
+                AccessError::WouldBlock
+            );
+            drop(value);
+
+            let first =
+                AsyncWriteGuard::try_map(container.write_async().await, |c| c.1.first_mut());
+            *first.unwrap() = 10;
+
+            let snapshot = container.get_cloned_async().await;
+            assert_eq!(snapshot.0.value, 3);
+            assert_eq!(snapshot.1, vec![10, 2]);
+        });
+    }
+
+    #[test]
+    fn test_async_owned_guard_map() {
+        use shared_container::{OwnedAsyncReadGuard, OwnedAsyncWriteGuard};
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new((TestData { value: 1 }, vec![1, 2]));
+
+            let data = OwnedAsyncWriteGuard::map(container.write_owned_async().await, |c| &mut c.0);
+            let handle = tokio::spawn(async move {
+                let mut data = data;
+                data.value = 5;
+            });
+            handle.await.unwrap();
+
+            let value =
+                OwnedAsyncReadGuard::map(container.read_owned_async().await, |c| &c.0.value);
+            assert_eq!(*value, 5);
+            let missing = OwnedAsyncReadGuard::try_map(value, |v| (*v > 10).then_some(v));
+            assert!(missing.is_err());
+        });
+    }
+
+    #[test]
+    fn test_async_write_guard_downgrade() {
+        use shared_container::{AccessError, AsyncWriteGuard, OwnedAsyncWriteGuard};
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let container = AsyncShared::new(TestData { value: 0 });
+
+            let mut guard = container.write_async().await;
+            guard.value = 1;
+            let guard = AsyncWriteGuard::downgrade(guard);
+            assert_eq!(guard.value, 1);
+            assert!(container.try_read_async().is_ok());
+            assert_eq!(
+                container.try_write_async().unwrap_err(),
+                AccessError::WouldBlock
+            );
+            drop(guard);
+
+            let mut guard = container.write_owned_async().await;
+            guard.value = 2;
+            let guard = OwnedAsyncWriteGuard::downgrade(guard);
+            assert_eq!(guard.value, 2);
+            assert!(container.try_read_owned_async().is_ok());
+            assert_eq!(
+                container.try_write_owned_async().unwrap_err(),
+                AccessError::WouldBlock
+            );
+        });
+    }
+
+    fn paused_runtime() -> Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .unwrap()
     }
-}
 
-#[cfg(feature = "async")]
-#[cfg(test)]
-mod async_shared_tests {
-    use shared_container::{AsyncShared, AsyncAccess};
-    use tokio::runtime::Runtime;
+    #[test]
+    fn test_async_shared_timeout_expires() {
+        use shared_container::AccessError;
+        use std::time::Duration;
 
-    #[derive(Debug, Clone, PartialEq)]
-    struct TestData {
-        value: i32,
+        paused_runtime().block_on(async {
+            let container = AsyncShared::new(TestData { value: 42 });
+            let _writer = container.write_async().await;
+
+            let start = tokio::time::Instant::now();
+            let result = container.read_timeout_async(Duration::from_secs(30)).await;
+            assert_eq!(result.unwrap_err(), AccessError::Timeout);
+            assert!(start.elapsed() >= Duration::from_secs(30));
+
+            let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+            let result = container.write_until_async(deadline.into_std()).await;
+            assert_eq!(result.unwrap_err(), AccessError::Timeout);
+        });
     }
 
     #[test]
-    fn test_async_shared_new_and_read() {
+    fn test_async_shared_timeout_acquires_after_release() {
+        use std::time::Duration;
+
+        paused_runtime().block_on(async {
+            let container = AsyncShared::new(TestData { value: 42 });
+            let writer = container.write_async().await;
+
+            let other = container.clone();
+            let waiter = tokio::spawn(async move {
+                let guard = other.read_timeout_async(Duration::from_secs(60)).await;
+                guard.map(|g| g.value)
+            });
+
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            let mut writer = writer;
+            writer.value = 100;
+            drop(writer);
+
+            assert_eq!(waiter.await.unwrap().unwrap(), 100);
+
+            let deadline = tokio::time::Instant::now() + Duration::from_secs(1);
+            let guard = container.read_until_async(deadline.into_std()).await;
+            assert_eq!(guard.unwrap().value, 100);
+            let guard = container.write_timeout_async(Duration::ZERO).await;
+            assert!(guard.is_ok());
+        });
+    }
+
+    #[test]
+    fn test_async_changed_resolves_after_write_release() {
+        use shared_container::{AsyncWriteGuard, OwnedAsyncWriteGuard};
+        use std::time::Duration;
+
+        paused_runtime().block_on(async {
+            let container = AsyncShared::new(TestData { value: 0 });
+
+            // Nothing written yet
+            let pending = tokio::time::timeout(Duration::from_secs(1), container.changed());
+            assert!(pending.await.is_err());
+
+            // A guard held when `changed` is called counts once released
+            let mut guard = container.write_async().await;
+            let changed = container.changed();
+            guard.value = 1;
+            drop(guard);
+            changed.await;
+
+            // Reading does not count, mapped and owned write guards do
+            let changed = container.changed();
+            drop(container.read_async().await);
+            let mut value = AsyncWriteGuard::map(container.write_async().await, |d| &mut d.value);
+            *value = 2;
+            drop(value);
+            changed.await;
+
+            let changed = container.changed();
+            let guard = OwnedAsyncWriteGuard::downgrade(container.write_owned_async().await);
+            changed.await;
+            drop(guard);
+
+            let waiter = tokio::spawn({
+                let container = container.clone();
+                async move { container.changed().await }
+            });
+            tokio::task::yield_now().await;
+            container.with_mut_async(|d| d.value = 3).await;
+            waiter.await.unwrap();
+        });
+    }
+
+    #[test]
+    fn test_async_wait_until_predicate() {
         let rt = Runtime::new().unwrap();
         rt.block_on(async {
-            let container = AsyncShared::new(TestData { value: 42 });
-            let guard = container.read_async().await;
-            assert_eq!(guard.value, 42);
+            let container = AsyncShared::new(TestData { value: 0 });
+
+            // Already satisfied
+            let guard = container.wait_until(|d| d.value == 0).await;
+            drop(guard);
+
+            let writer = container.clone();
+            let task = tokio::spawn(async move {
+                for _ in 0..50 {
+                    writer.with_mut_async(|d| d.value += 1).await;
+                    tokio::task::yield_now().await;
+                }
+            });
+
+            let guard = container.wait_until(|d| d.value >= 25).await;
+            assert!(guard.value >= 25);
+            drop(guard);
+
+            let guard = container.wait_until(|d| d.value == 50).await;
+            assert_eq!(guard.value, 50);
+            drop(guard);
+            task.await.unwrap();
         });
     }
 
+    #[cfg(feature = "stream")]
     #[test]
-    fn test_async_shared_write() {
+    fn test_async_snapshots_stream() {
+        use futures_util::StreamExt;
+
         let rt = Runtime::new().unwrap();
         rt.block_on(async {
-            let container = AsyncShared::new(TestData { value: 42 });
+            let container = AsyncShared::new(TestData { value: 0 });
+            let mut snapshots = container.snapshots();
+            assert_eq!(snapshots.next().await, Some(TestData { value: 0 }));
+
+            // Writes made while the consumer is away collapse into the latest value
+            container.replace_async(TestData { value: 1 }).await;
+            container.replace_async(TestData { value: 2 }).await;
+            assert_eq!(snapshots.next().await, Some(TestData { value: 2 }));
+
+            // A consumer waiting in another task is woken by the write
+            let consumer = tokio::spawn(async move {
+                let next = snapshots.next().await;
+                (next, snapshots)
+            });
+            tokio::task::yield_now().await;
+            container.replace_async(TestData { value: 3 }).await;
+            let (next, mut snapshots) = consumer.await.unwrap();
+            assert_eq!(next, Some(TestData { value: 3 }));
+
+            // The stream ends once every strong handle is gone
+            drop(container);
+            assert_eq!(snapshots.next().await, None);
+            assert_eq!(snapshots.next().await, None);
+        });
+    }
+}
+
+/// The async containers only use runtime-independent parts of tokio, so they must
+/// work under any executor.
+#[cfg(feature = "async")]
+#[cfg(test)]
+mod executor_agnostic_tests {
+    use futures_executor::block_on;
+    use shared_container::{AsyncAccess, AsyncShared, AsyncSharedMutex};
+    use std::time::Duration;
+
+    async fn bump<C: AsyncAccess<i32> + Sync>(container: &C) -> i32 {
+        container.update_async(|v| v + 1).await;
+        container.get_cloned_async().await
+    }
+
+    #[test]
+    fn test_async_access_under_block_on() {
+        let container = AsyncShared::new(1);
+        block_on(async {
+            assert_eq!(bump(&container).await, 2);
+            *container.write_async().await *= 10;
+            assert_eq!(*container.read_async().await, 20);
+            assert_eq!(container.with_async(|v| v + 1).await, 21);
+        });
+    }
+
+    #[test]
+    fn test_waiting_writer_is_woken_by_another_thread() {
+        let container = AsyncShared::new(0);
+        let mut guard = block_on(container.write_owned_async());
+        let holder = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            *guard = 1;
+        });
+
+        block_on(async {
+            *container.write_async().await += 1;
+        });
+        assert_eq!(block_on(container.get_cloned_async()), 2);
+        holder.join().unwrap();
+    }
+
+    #[test]
+    fn test_change_notification_under_block_on() {
+        let container = AsyncShared::new(0);
+        let writer = container.clone();
+        let handle = std::thread::spawn(move || {
+            for _ in 0..3 {
+                std::thread::sleep(Duration::from_millis(5));
+                block_on(writer.update_async(|v| v + 1));
+            }
+        });
+
+        block_on(async {
+            let guard = container.wait_until(|v| *v == 3).await;
+            assert_eq!(*guard, 3);
+        });
+        handle.join().unwrap();
+
+        let changed = container.changed();
+        block_on(container.replace_async(10));
+        block_on(changed);
+    }
+
+    #[test]
+    fn test_async_mutex_under_block_on() {
+        let counter = AsyncSharedMutex::new(std::cell::Cell::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let counter = counter.clone();
+                std::thread::spawn(move || block_on(counter.with_lock(|c| c.set(c.get() + 1))))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(block_on(counter.lock()).get(), 4);
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn test_snapshots_under_block_on() {
+        use futures_util::StreamExt;
+
+        let container = AsyncShared::new(1);
+        let mut snapshots = container.snapshots();
+        block_on(async {
+            assert_eq!(snapshots.next().await, Some(1));
+            container.replace_async(2).await;
+            assert_eq!(snapshots.next().await, Some(2));
+        });
+        drop(container);
+        assert_eq!(block_on(snapshots.next()), None);
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg(test)]
+mod shared_async_access_tests {
+    use shared_container::{
+        AccessError, AsyncAccess, AsyncReadGuard, AsyncWriteGuard, Shared, SyncAccess,
+    };
+    use futures_util::FutureExt;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    async fn bump<C: AsyncAccess<i32> + Sync>(container: &C) -> i32 {
+        container.update_async(|v| v + 1).await;
+        container.get_cloned_async().await
+    }
+
+    #[tokio::test]
+    async fn test_shared_in_generic_async_code() {
+        let container = Shared::new(1);
+        assert_eq!(bump(&container).await, 2);
+        *container.write_async().await *= 10;
+        assert_eq!(container.with_async(|v| v + 1).await, 21);
+        assert_eq!(container.replace_async(5).await, 20);
+
+        // The sync and async views share one lock
+        let guard = container.read_async().await;
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        assert_eq!(
+            container.try_write_async().unwrap_err(),
+            AccessError::WouldBlock
+        );
+        drop(guard);
+        assert_eq!(container.get_cloned().unwrap(), 5);
+    }
 
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_contended_shared_does_not_block_executor() {
+        let container = Shared::new(0);
+        let progress = Arc::new(AtomicUsize::new(0));
+
+        let holder = {
+            let container = container.clone();
+            let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+            let handle = std::thread::spawn(move || {
+                let mut guard = container.write().unwrap();
+                locked_tx.send(()).unwrap();
+                std::thread::sleep(Duration::from_millis(50));
+                *guard = 1;
+            });
+            locked_rx.recv().unwrap();
+            handle
+        };
+
+        // Runs on the same thread while the read below waits
+        let ticker = tokio::spawn({
+            let progress = progress.clone();
+            async move {
+                loop {
+                    progress.fetch_add(1, Ordering::SeqCst);
+                    tokio::task::yield_now().await;
+                }
+            }
+        });
+
+        assert_eq!(*container.read_async().await, 1);
+        assert!(progress.load(Ordering::SeqCst) > 0);
+        ticker.abort();
+        holder.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_async_writer_is_not_starved_by_readers() {
+        let container = Shared::new(0);
+        let stop = Arc::new(AtomicBool::new(false));
+        // Two overlapping readers keep the lock held in shared mode
+        let readers: Vec<_> = (0..2)
+            .map(|_| {
+                let container = container.clone();
+                let stop = stop.clone();
+                std::thread::spawn(move || {
+                    while !stop.load(Ordering::SeqCst) {
+                        let _guard = container.read().unwrap();
+                        std::thread::sleep(Duration::from_millis(1));
+                    }
+                })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let written = tokio::time::timeout(Duration::from_secs(5), async {
+            *container.write_async().await = 1;
+        })
+        .await;
+        stop.store(true, Ordering::SeqCst);
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert!(written.is_ok());
+        assert_eq!(container.get_cloned().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_async_writer_lets_readers_in() {
+        let container = Shared::new(0);
+        let guard = container.read_async().await;
+
+        let mut write = Box::pin(container.write_async());
+        assert!((&mut write).now_or_never().is_none());
+        // The waiting writer holds back new readers
+        assert_eq!(
+            container.try_read().map(|_| ()).unwrap_err(),
+            AccessError::WouldBlock
+        );
+        drop(write);
+        assert!(container.try_read().is_ok());
+
+        drop(guard);
+        assert!(container.write_async().now_or_never().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_minimal_async_access_impl() {
+        // An implementor written before the newer methods existed still compiles
+        struct Legacy(Shared<i32>);
+
+        impl AsyncAccess<i32> for Legacy {
+            async fn read_async<'a>(&'a self) -> AsyncReadGuard<'a, i32>
+            where
+                i32: 'a,
             {
+                self.0.read_async().await
+            }
+
+            async fn write_async<'a>(&'a self) -> AsyncWriteGuard<'a, i32>
+            where
+                i32: 'a,
+            {
+                self.0.write_async().await
+            }
+
+            async fn get_cloned_async(&self) -> i32 {
+                self.0.get_cloned_async().await
+            }
+        }
+
+        let a = Legacy(Shared::new(1));
+        let b = Legacy(Shared::new(2));
+        assert_eq!(a.try_read_async().unwrap_err(), AccessError::UnsupportedMode);
+        a.swap_async(&b).await;
+        a.swap_async(&a).await;
+        assert_eq!(a.get_cloned_async().await, 2);
+        assert_eq!(b.get_cloned_async().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_shared_async_guards_map_and_downgrade() {
+        let container = Shared::new((1, String::from("a")));
+        {
+            let mut text = AsyncWriteGuard::map(container.write_async().await, |p| &mut p.1);
+            text.push('b');
+        }
+        let first = AsyncReadGuard::map(container.read_async().await, |p| &p.0);
+        assert_eq!(*first, 1);
+        drop(first);
+
+        let mut guard = container.write_async().await;
+        guard.0 = 2;
+        let guard = AsyncWriteGuard::downgrade(guard);
+        assert!(container.try_read_async().is_ok());
+        assert_eq!(*guard, (2, String::from("ab")));
+    }
+
+    #[tokio::test]
+    async fn test_shared_async_guard_across_spawn() {
+        let container = Shared::new(vec![1]);
+        let task = tokio::spawn({
+            let container = container.clone();
+            async move {
                 let mut guard = container.write_async().await;
-                guard.value = 100;
+                tokio::task::yield_now().await;
+                guard.push(2);
             }
+        });
+        task.await.unwrap();
 
-            let guard = container.read_async().await;
-            assert_eq!(guard.value, 100);
+        let other = Shared::new(vec![3]);
+        container.swap_async(&other).await;
+        container.swap_async(&container.clone()).await;
+        assert_eq!(container.get_cloned_async().await, vec![3]);
+        assert_eq!(other.get_cloned_async().await, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_shared_async_access_on_poisoned_container() {
+        let container = Shared::new(0);
+        let other = container.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = other.write().unwrap();
+            panic!("writer failed");
+        })
+        .join();
+
+        assert_eq!(
+            container.try_read_async().map(|_| ()).unwrap_err(),
+            AccessError::Poisoned
+        );
+        // The futures cannot fail, so they hand out the guard anyway
+        assert_eq!(*container.read_async().await, 0);
+    }
+}
+
+#[cfg(feature = "async")]
+#[cfg(test)]
+mod blocking_access_tests {
+    use shared_container::{
+        AccessError, AsyncAccess, AsyncShared, AsyncSharedMutex, SharedAny, SyncAccess,
+        SyncReadGuard, SyncWriteGuard,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn test_blocking_access_outside_runtime() {
+        let container = AsyncShared::new(vec![1, 2]);
+        container.blocking_write().unwrap().push(3);
+        assert_eq!(container.blocking_read().unwrap().len(), 3);
+        assert_eq!(container.blocking_get_cloned().unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_blocking_write_waits_for_reader() {
+        let container = AsyncShared::new(0);
+        let guard = futures_executor::block_on(container.read_async());
+        let writer = {
+            let container = container.clone();
+            std::thread::spawn(move || {
+                *container.blocking_write().unwrap() = 1;
+            })
+        };
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(*guard, 0);
+        drop(guard);
+        writer.join().unwrap();
+        assert_eq!(container.blocking_get_cloned().unwrap(), 1);
+    }
+
+    #[cfg(feature = "tokio-rt")]
+    #[tokio::test]
+    async fn test_blocking_inside_runtime_errors() {
+        let container = AsyncShared::new(1);
+        assert_eq!(
+            container.blocking_read().map(|_| ()).unwrap_err(),
+            AccessError::BlockingInRuntime
+        );
+        assert_eq!(
+            container.blocking_write().map(|_| ()).unwrap_err(),
+            AccessError::BlockingInRuntime
+        );
+        assert_eq!(
+            container.blocking_get_cloned().unwrap_err(),
+            AccessError::BlockingInRuntime
+        );
+
+        let any: SharedAny<i32> = container.into();
+        assert_eq!(
+            any.read().map(|_| ()).unwrap_err(),
+            AccessError::BlockingInRuntime
+        );
+
+        let mutex = AsyncSharedMutex::new(1);
+        assert_eq!(
+            mutex.blocking_lock().map(|_| ()).unwrap_err(),
+            AccessError::BlockingInRuntime
+        );
+        let any: SharedAny<i32> = mutex.into();
+        assert_eq!(any.get_cloned().unwrap_err(), AccessError::BlockingInRuntime);
+        assert_eq!(*any.try_read().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_shared_any_sync_access_to_async_mutex() {
+        let mutex = AsyncSharedMutex::new(vec![1]);
+        mutex.blocking_lock().unwrap().push(2);
+        let any: SharedAny<Vec<i32>> = mutex.clone().into();
+
+        any.write().unwrap().push(3);
+        assert_eq!(any.read().unwrap().len(), 3);
+        assert_eq!(any.get_cloned().unwrap(), [1, 2, 3]);
+
+        // Reads take the mutex exclusively
+        let guard = any.try_read().unwrap();
+        assert_eq!(any.try_read().map(|_| ()).unwrap_err(), AccessError::WouldBlock);
+        assert_eq!(any.try_write().map(|_| ()).unwrap_err(), AccessError::WouldBlock);
+        drop(guard);
+        any.try_write().unwrap().clear();
+        assert!(mutex.blocking_lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_shared_any_sync_access_to_async() {
+        let container = AsyncShared::new((1, String::from("a")));
+        let any: SharedAny<(i32, String)> = container.clone().into();
+
+        assert_eq!(any.read().unwrap().0, 1);
+        any.write().unwrap().1.push('b');
+        assert_eq!(any.get_cloned().unwrap(), (1, String::from("ab")));
+
+        // Split guards keep the lock until the last one is dropped
+        let (mut number, text) = SyncWriteGuard::map_split(any.write().unwrap(), |p| {
+            (&mut p.0, &mut p.1)
         });
+        *number += 1;
+        drop(number);
+        assert_eq!(
+            container.try_read_async().map(|_| ()).unwrap_err(),
+            AccessError::WouldBlock
+        );
+        drop(text);
+        assert_eq!(container.blocking_read().unwrap().0, 2);
     }
 
     #[test]
-    fn test_async_shared_clone() {
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async {
-            let container1 = AsyncShared::new(TestData { value: 42 });
-            let container2 = container1.clone();
+    fn test_shared_any_downgrade_async_write() {
+        let container = AsyncShared::new(1);
+        let any: SharedAny<i32> = container.clone().into();
 
-            {
-                let mut guard = container2.write_async().await;
-                guard.value = 100;
+        let mut guard = any.write().unwrap();
+        *guard = 2;
+        let guard = SyncWriteGuard::downgrade(guard);
+        let (first, second) = SyncReadGuard::map_split(guard, |v| (v, v));
+        // Other readers get in, writers still wait
+        assert_eq!(*container.try_read_async().unwrap(), 2);
+        assert!(container.try_write_async().is_err());
+        drop(first);
+        drop(second);
+        assert!(container.try_write_async().is_ok());
+    }
+}
+
+#[cfg(test)]
+mod local_async_shared_tests {
+    use futures_util::FutureExt;
+    use shared_container::{
+        AccessError, LocalAsyncAccess, LocalAsyncReadGuard, LocalAsyncShared, LocalAsyncWriteGuard,
+    };
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use tokio::task::{LocalSet, yield_now};
+
+    fn run_local<F: std::future::Future>(future: F) -> F::Output {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        LocalSet::new().block_on(&rt, future)
+    }
+
+    #[test]
+    fn test_local_async_access_with_rc_payload() {
+        run_local(async {
+            let container = LocalAsyncShared::new(Rc::new(RefCell::new(vec![1])));
+            container.read_async().await.borrow_mut().push(2);
+            assert_eq!(*container.get_cloned_async().await.borrow(), vec![1, 2]);
+
+            let counter = LocalAsyncShared::new(1);
+            counter.update_async(|v| v + 1).await;
+            assert_eq!(counter.with_async(|v| v * 10).await, 20);
+            assert_eq!(counter.with_mut_async(|v| std::mem::replace(v, 5)).await, 2);
+            assert_eq!(counter.replace_async(6).await, 5);
+            assert_eq!(counter.take_async().await, 6);
+            assert_eq!(*counter.read_async().await, 0);
+        });
+    }
+
+    #[test]
+    fn test_local_async_tasks_share_container() {
+        run_local(async {
+            let container = LocalAsyncShared::new(0);
+            let handles: Vec<_> = (0..10)
+                .map(|_| {
+                    let container = container.clone();
+                    tokio::task::spawn_local(async move {
+                        let mut guard = container.write_async().await;
+                        let seen = *guard;
+                        yield_now().await;
+                        *guard = seen + 1;
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.await.unwrap();
             }
+            assert_eq!(*container.read_async().await, 10);
+        });
+    }
 
-            let guard = container1.read_async().await;
-            assert_eq!(guard.value, 100);
+    #[test]
+    fn test_local_async_writer_waits_for_readers() {
+        run_local(async {
+            let container = LocalAsyncShared::new(0);
+            let log = Rc::new(RefCell::new(Vec::new()));
+
+            let reader = container.read_async().await;
+            assert!(container.try_read_async().is_ok());
+            assert!(matches!(
+                container.try_write_async(),
+                Err(AccessError::WouldBlock)
+            ));
+
+            let writer = tokio::task::spawn_local({
+                let container = container.clone();
+                let log = log.clone();
+                async move {
+                    *container.write_async().await = 1;
+                    log.borrow_mut().push("writer");
+                }
+            });
+            yield_now().await;
+
+            // A queued writer keeps new readers out.
+            assert!(matches!(
+                container.try_read_async(),
+                Err(AccessError::WouldBlock)
+            ));
+            let late_reader = tokio::task::spawn_local({
+                let container = container.clone();
+                let log = log.clone();
+                async move {
+                    let value = *container.read_async().await;
+                    log.borrow_mut().push("late reader");
+                    value
+                }
+            });
+            yield_now().await;
+            assert!(log.borrow().is_empty());
+
+            drop(reader);
+            writer.await.unwrap();
+            assert_eq!(late_reader.await.unwrap(), 1);
+            assert_eq!(*log.borrow(), vec!["writer", "late reader"]);
         });
     }
 
     #[test]
-    fn test_async_shared_get_cloned() {
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async {
-            let container = AsyncShared::new(TestData { value: 42 });
-            let cloned = container.get_cloned_async().await;
-            assert_eq!(cloned, TestData { value: 42 });
+    fn test_local_async_cancelled_waiter_leaves_queue() {
+        run_local(async {
+            let container = LocalAsyncShared::new(0);
+            let reader = container.read_async().await;
+
+            // Polled once, queued behind the reader, then dropped.
+            assert!(container.write_async().now_or_never().is_none());
+            assert!(container.try_read_async().is_ok());
+
+            drop(reader);
+            assert!(container.try_write_async().is_ok());
+        });
+    }
 
+    #[test]
+    fn test_local_async_guard_map_and_downgrade() {
+        run_local(async {
+            let container = LocalAsyncShared::new((1, String::from("a")));
             {
-                let mut guard = container.write_async().await;
-                guard.value = 100;
+                let guard = container.write_async().await;
+                let mut name = LocalAsyncWriteGuard::map(guard, |pair| &mut pair.1);
+                name.push('b');
             }
+            let first = LocalAsyncReadGuard::map(container.read_async().await, |pair| &pair.0);
+            assert_eq!(*first, 1);
+            drop(first);
 
-            // Cloned value should remain unchanged
-            assert_eq!(cloned, TestData { value: 42 });
+            let mut guard = container.write_async().await;
+            guard.0 = 2;
+            let guard = LocalAsyncWriteGuard::downgrade(guard);
+            assert!(container.try_read_async().is_ok());
+            assert!(container.try_write_async().is_err());
+            assert_eq!(*guard, (2, String::from("ab")));
+        });
+    }
 
-            let new_clone = container.get_cloned_async().await;
-            assert_eq!(new_clone, TestData { value: 100 });
+    #[test]
+    fn test_local_async_swap_and_weak() {
+        run_local(async {
+            let a = LocalAsyncShared::new(1);
+            let b = LocalAsyncShared::new(2);
+            a.swap_async(&b).await;
+            a.swap_async(&a.clone()).await;
+            assert_eq!(
+                (a.get_cloned_async().await, b.get_cloned_async().await),
+                (2, 1)
+            );
+
+            let weak = a.downgrade();
+            assert_eq!(*weak.upgrade().unwrap().read_async().await, 2);
+            drop(a);
+            assert!(weak.upgrade().is_none());
         });
     }
 
     #[test]
-    fn test_async_shared_weak() {
-        let rt = Runtime::new().unwrap();
-        rt.block_on(async {
-            let container = AsyncShared::new(TestData { value: 42 });
-            let weak = container.downgrade();
+    fn test_local_async_debug() {
+        let container = LocalAsyncShared::new(7);
+        let weak = container.downgrade();
+        assert_eq!(format!("{:?}", container), "LocalAsyncShared { value: 7 }");
+        assert_eq!(format!("{:?}", weak), "WeakLocalAsyncShared { value: 7 }");
+
+        let guard = container.try_write_async().unwrap();
+        assert_eq!(
+            format!("{:?}", container),
+            "LocalAsyncShared { value: <busy>, strong: 1, weak: 1 }"
+        );
+        drop(guard);
+        drop(container);
+        assert_eq!(
+            format!("{:?}", weak),
+            "WeakLocalAsyncShared { value: <dropped>, strong: 0, weak: 0 }"
+        );
+    }
+}
+
+#[cfg(test)]
+mod local_shared_tests {
+    use shared_container::{AccessError, LocalShared, SyncAccess, SyncReadGuard, SyncWriteGuard};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_local_shared_borrow_conflicts() {
+        let container = LocalShared::new(Rc::new(Cell::new(1)));
+
+        let first = container.read().unwrap();
+        let second = container.read().unwrap();
+        assert_eq!(container.write().unwrap_err(), AccessError::BorrowConflict);
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        drop((first, second));
+
+        let writer = container.write().unwrap();
+        writer.set(2);
+        assert_eq!(container.read().unwrap_err(), AccessError::BorrowConflict);
+        assert_eq!(container.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(writer);
+
+        assert_eq!(container.get_cloned().unwrap().get(), 2);
+    }
+
+    #[test]
+    fn test_local_shared_closures_swap_and_weak() {
+        let a = LocalShared::new(vec![1]);
+        let b = LocalShared::new(vec![2]);
+
+        a.with_mut(|v| v.push(3)).unwrap();
+        a.swap(&b).unwrap();
+        a.swap(&a.clone()).unwrap();
+        assert_eq!(a.get_cloned().unwrap(), vec![2]);
+        assert_eq!(b.take().unwrap(), vec![1, 3]);
+
+        // A held borrow makes the swap fail instead of blocking
+        let guard = b.read().unwrap();
+        assert_eq!(a.swap(&b).unwrap_err(), AccessError::BorrowConflict);
+        drop(guard);
+
+        let weak = a.downgrade();
+        assert_eq!(weak.upgrade().unwrap().get_cloned().unwrap(), vec![2]);
+        drop(a);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_local_shared_guard_map_split_and_downgrade() {
+        let container = LocalShared::new((1, String::from("a")));
+
+        let (mut num, mut text) =
+            SyncWriteGuard::map_split(container.write().unwrap(), |p| (&mut p.0, &mut p.1));
+        *num += 1;
+        text.push('b');
+        drop(num);
+        assert!(container.try_read().is_err());
+        drop(text);
+
+        let mut guard = container.write().unwrap();
+        guard.0 = 3;
+        let guard = SyncWriteGuard::downgrade(guard);
+        let text = SyncReadGuard::map(container.read().unwrap(), |p| p.1.as_str());
+        assert_eq!((guard.0, &*text), (3, "ab"));
+        assert!(container.try_write().is_err());
+    }
+
+    #[test]
+    fn test_local_shared_not_poisoned_by_panic() {
+        let container = LocalShared::new(0);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut guard = container.write().unwrap();
+            *guard = 1;
+            panic!("handler failed");
+        }));
+        assert!(result.is_err());
+        assert_eq!(container.get_cloned().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_local_shared_debug() {
+        let container = LocalShared::new(7);
+        assert_eq!(format!("{:?}", container), "LocalShared { value: 7 }");
+        let reader = container.read().unwrap();
+        assert_eq!(format!("{:?}", container), "LocalShared { value: 7 }");
+        drop(reader);
+
+        let _guard = container.write().unwrap();
+        assert_eq!(
+            format!("{:?}", container.clone()),
+            "LocalShared { value: <busy>, strong: 2, weak: 0 }"
+        );
+    }
+}
+
+#[cfg(test)]
+mod thread_bound_shared_tests {
+    use shared_container::{AccessError, SyncAccess, ThreadBoundShared};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_thread_bound_shared_is_send_sync_for_rc() {
+        assert_send_sync::<ThreadBoundShared<Rc<Cell<i32>>>>();
+    }
+
+    #[test]
+    fn test_thread_bound_shared_owner_access() {
+        let container = ThreadBoundShared::new(Rc::new(Cell::new(1)));
+        assert_eq!(container.owner_thread(), std::thread::current().id());
+
+        let reader = container.read().unwrap();
+        assert_eq!(container.write().unwrap_err(), AccessError::BorrowConflict);
+        assert_eq!(container.try_write().unwrap_err(), AccessError::WouldBlock);
+        drop(reader);
+
+        container.write().unwrap().set(2);
+        assert_eq!(container.get_cloned().unwrap().get(), 2);
+        assert_eq!(
+            format!("{:?}", container),
+            "ThreadBoundShared { value: Cell { value: 2 } }"
+        );
+        let _writer = container.write().unwrap();
+        assert_eq!(
+            format!("{:?}", container),
+            "ThreadBoundShared { value: <busy>, strong: 1, weak: 0 }"
+        );
+    }
+
+    #[test]
+    fn test_thread_bound_shared_foreign_thread() {
+        let container = ThreadBoundShared::new(Rc::new(Cell::new(1)));
+        let other = ThreadBoundShared::new(Rc::new(Cell::new(2)));
+        let (handle, other_handle) = (container.clone(), other.clone());
 
-            let upgraded = weak.upgrade().unwrap();
-            {
-                let mut guard = upgraded.write_async().await;
-                guard.value = 100;
-            }
+        let errors = std::thread::spawn(move || {
+            let errors = [
+                handle.read().map(|_| ()).unwrap_err(),
+                handle.write().map(|_| ()).unwrap_err(),
+                handle.try_read().map(|_| ()).unwrap_err(),
+                handle.with(|_| ()).unwrap_err(),
+                handle.swap(&other_handle).unwrap_err(),
+            ];
+            assert_eq!(
+                format!("{:?}", handle),
+                "ThreadBoundShared { value: <other thread>, strong: 2, weak: 0 }"
+            );
+            // Moved back so the last handles are dropped on the owner thread.
+            (errors, handle, other_handle)
+        })
+        .join()
+        .unwrap();
 
-            {
-                let guard = container.read_async().await;
-                assert_eq!(guard.value, 100);
-            }
+        assert!(errors.0.iter().all(|e| *e == AccessError::WrongThread));
+        container.swap(&other).unwrap();
+        assert_eq!(container.get_cloned().unwrap().get(), 2);
+    }
 
-            drop(container);
-            drop(upgraded);
+    #[test]
+    fn test_thread_bound_shared_weak_and_drop() {
+        let value = Rc::new(());
+        let container = ThreadBoundShared::new(value.clone());
+        let weak = container.downgrade();
+        assert!(weak.upgrade().is_some());
 
-            assert!(weak.upgrade().is_none());
-        });
+        drop(container);
+        assert!(weak.upgrade().is_none());
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn test_thread_bound_shared_leaks_when_dropped_elsewhere() {
+        let value = Rc::new(());
+        let container = ThreadBoundShared::new(value.clone());
+        std::thread::spawn(move || drop(container)).join().unwrap();
+        assert_eq!(Rc::strong_count(&value), 2);
     }
 }
 
@@ -281,6 +2756,91 @@ mod shared_any_tests {
         assert!(weak.upgrade().is_none());
     }
 
+    #[test]
+    fn test_shared_any_from_mutex() {
+        use shared_container::SharedMutex;
+
+        let any: SharedAny<TestData> = SharedMutex::new(TestData { value: 42 }).into();
+        any.write().unwrap().value = 100;
+        assert_eq!(any.get_cloned().unwrap(), TestData { value: 100 });
+
+        let weak = any.downgrade();
+        assert_eq!(weak.upgrade().unwrap().read().unwrap().value, 100);
+
+        let other: SharedAny<TestData> = Shared::new(TestData { value: 1 }).into();
+        assert!(any.swap(&other).is_err());
+    }
+
+    #[test]
+    fn test_shared_any_is_send_sync() {
+        fn assert_send_sync<S: Send + Sync>() {}
+        assert_send_sync::<SharedAny<TestData>>();
+        assert_send_sync::<shared_container::WeakSharedAny<TestData>>();
+
+        let any: SharedAny<TestData> = Shared::new(TestData { value: 1 }).into();
+        let handle = any.clone();
+        std::thread::spawn(move || handle.with_mut(|d| d.value += 1).unwrap())
+            .join()
+            .unwrap();
+        assert_eq!(any.get_cloned().unwrap().value, 2);
+    }
+
+    #[test]
+    fn test_local_shared_any() {
+        use shared_container::{AccessError, LocalShared, LocalSharedAny};
+
+        let local: LocalSharedAny<TestData> = LocalShared::new(TestData { value: 42 }).into();
+        let guard = local.read().unwrap();
+        assert_eq!(local.write().unwrap_err(), AccessError::BorrowConflict);
+        drop(guard);
+        local.with_mut(|d| d.value += 1).unwrap();
+        assert!(matches!(local, LocalSharedAny::Local(_)));
+
+        let weak = local.downgrade();
+        assert!(weak.upgrade().unwrap().ptr_eq(&local));
+        assert_eq!(local.strong_count(), 1);
+        assert_eq!(
+            format!("{:?}", local),
+            "Local(LocalShared { value: TestData { value: 43 } })"
+        );
+
+        let any: LocalSharedAny<TestData> = Shared::new(TestData { value: 1 }).into();
+        assert!(matches!(any, LocalSharedAny::Any(SharedAny::Sync(_))));
+        assert_eq!(local.swap(&any).unwrap_err(), AccessError::UnsupportedMode);
+        assert!(local != any);
+        any.swap(&LocalSharedAny::from(SharedAny::from(Shared::new(TestData { value: 43 }))))
+            .unwrap();
+        assert!(local == any);
+        assert_eq!(local.into_inner().unwrap().unwrap().value, 43);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_shared_any_from_async_mutex() {
+        use shared_container::{AccessError, AsyncAccess, AsyncSharedMutex};
+        use tokio::runtime::Runtime;
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let any: SharedAny<TestData> = AsyncSharedMutex::new(TestData { value: 42 }).into();
+            assert_eq!(any.get_cloned_async().await, TestData { value: 42 });
+            assert_eq!(any.try_read().unwrap().value, 42);
+            #[cfg(feature = "tokio-rt")]
+            assert_eq!(any.read().map(|_| ()).unwrap_err(), AccessError::BlockingInRuntime);
+
+            // Reads take the mutex too
+            any.write_async().await.value += 1;
+            let guard = any.read_async().await;
+            assert_eq!(guard.value, 43);
+            assert_eq!(
+                any.try_read_async().map(|_| ()).unwrap_err(),
+                AccessError::WouldBlock
+            );
+            drop(guard);
+            assert_eq!(any.try_read_async().unwrap().value, 43);
+        });
+    }
+
     #[cfg(feature = "async")]
     #[test]
     fn test_shared_any_unsupported_mode_error() {
@@ -292,18 +2852,735 @@ mod shared_any_tests {
             let async_shared = AsyncShared::new(TestData { value: 42 });
             let any: SharedAny<TestData> = async_shared.into();
 
-            // Trying to use sync methods on async container should return UnsupportedMode error
-            let result = any.read();
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), AccessError::UnsupportedMode);
+            // Blocking sync methods refuse to stall the runtime
+            #[cfg(feature = "tokio-rt")]
+            {
+                let result = any.read();
+                assert!(result.is_err());
+                assert_eq!(result.unwrap_err(), AccessError::BlockingInRuntime);
+
+                let result = any.write();
+                assert!(result.is_err());
+                assert_eq!(result.unwrap_err(), AccessError::BlockingInRuntime);
+
+                let result = any.get_cloned();
+                assert!(result.is_err());
+                assert_eq!(result.unwrap_err(), AccessError::BlockingInRuntime);
+            }
+
+            // The non-blocking sync methods work on an async container
+            assert_eq!(any.try_read().unwrap().value, 42);
+            let guard = any.try_write().unwrap();
+            assert_eq!(any.try_read().map(|_| ()).unwrap_err(), AccessError::WouldBlock);
+            drop(guard);
+        });
+    }
+
+    #[test]
+    fn test_shared_any_try_read_write() {
+        use shared_container::AccessError;
+
+        let any: SharedAny<TestData> = Shared::new(TestData { value: 42 }).into();
+
+        let guard = any.try_write().unwrap();
+        assert_eq!(any.try_read().unwrap_err(), AccessError::WouldBlock);
+        drop(guard);
+
+        assert_eq!(any.try_read().unwrap().value, 42);
+    }
+
+    #[test]
+    fn test_shared_any_closure_access() {
+        let any: SharedAny<TestData> = Shared::new(TestData { value: 42 }).into();
+        let other: SharedAny<TestData> = Shared::new(TestData { value: 7 }).into();
+
+        assert_eq!(any.with(|d| d.value).unwrap(), 42);
+        any.with_mut(|d| d.value += 1).unwrap();
+        any.update(|d| TestData { value: d.value * 2 }).unwrap();
+        assert_eq!(any.replace(TestData { value: 1 }).unwrap().value, 86);
+
+        any.swap(&other).unwrap();
+        assert_eq!(any.get_cloned().unwrap().value, 7);
+        assert_eq!(other.get_cloned().unwrap().value, 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_shared_any_closure_access_async() {
+        use shared_container::{AsyncAccess, AsyncShared};
+        use tokio::runtime::Runtime;
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(async {
+            let any: SharedAny<TestData> = AsyncShared::new(TestData { value: 42 }).into();
+            let other: SharedAny<TestData> = AsyncShared::new(TestData { value: 7 }).into();
+
+            assert_eq!(any.with_async(|d| d.value).await, 42);
+            any.with_mut_async(|d| d.value += 1).await;
+            assert_eq!(any.replace_async(TestData { value: 1 }).await.value, 43);
+
+            any.swap_async(&other).await;
+            assert_eq!(any.get_cloned_async().await.value, 7);
+            assert_eq!(other.get_cloned_async().await.value, 1);
+
+            // Sync closure methods cannot block inside the runtime
+            #[cfg(feature = "tokio-rt")]
+            assert_eq!(
+                any.with(|d| d.value).unwrap_err(),
+                shared_container::AccessError::BlockingInRuntime
+            );
+        });
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_shared_any_try_async() {
+        use shared_container::{AccessError, AsyncAccess, AsyncShared};
+
+        let any: SharedAny<TestData> = AsyncShared::new(TestData { value: 42 }).into();
+        let guard = any.try_write_async().unwrap();
+        assert_eq!(any.try_read_async().unwrap_err(), AccessError::WouldBlock);
+        drop(guard);
+        assert_eq!(any.try_read_async().unwrap().value, 42);
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_shared_any_read_async_on_every_variant() {
+        use shared_container::{AsyncAccess, AsyncShared, AsyncSharedMutex, SharedMutex};
+
+        let variants: [SharedAny<TestData>; 4] = [
+            Shared::new(TestData { value: 1 }).into(),
+            SharedMutex::new(TestData { value: 2 }).into(),
+            AsyncShared::new(TestData { value: 3 }).into(),
+            AsyncSharedMutex::new(TestData { value: 4 }).into(),
+        ];
+        for (expected, any) in (1..).zip(&variants) {
+            assert_eq!(any.read_async().await.value, expected);
+            assert_eq!(any.write_async().await.value, expected);
+            assert_eq!(any.get_cloned_async().await.value, expected);
+            assert_eq!(any.try_read_async().unwrap().value, expected);
+        }
+
+        // Every variant can be locked from a spawned task
+        let tasks = variants.map(|any| tokio::spawn(async move { any.read_async().await.value }));
+        for (expected, task) in (1..).zip(tasks) {
+            assert_eq!(task.await.unwrap(), expected);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_shared_any_async_access_to_sync() {
+        use shared_container::{
+            AccessError, AsyncAccess, AsyncShared, AsyncSharedMutex, SharedMutex,
+        };
+
+        let sync_any: SharedAny<TestData> = Shared::new(TestData { value: 1 }).into();
+        let mutex_any: SharedAny<TestData> = SharedMutex::new(TestData { value: 2 }).into();
+        let async_any: SharedAny<TestData> = AsyncShared::new(TestData { value: 3 }).into();
+        let async_mutex_any: SharedAny<TestData> =
+            AsyncSharedMutex::new(TestData { value: 4 }).into();
+
+        sync_any.with_mut_async(|d| d.value += 10).await;
+        assert_eq!(sync_any.with_async(|d| d.value).await, 11);
+        mutex_any.update_async(|d| TestData { value: d.value * 2 }).await;
+        assert_eq!(mutex_any.get_cloned_async().await.value, 4);
+
+        // The sync view shares the lock
+        let guard = sync_any.read_async().await;
+        assert_eq!(
+            sync_any.try_write().map(|_| ()).unwrap_err(),
+            AccessError::WouldBlock
+        );
+        drop(guard);
+
+        // Values can be swapped across modes
+        sync_any.swap_async(&async_any).await;
+        mutex_any.swap_async(&async_mutex_any).await;
+        sync_any.swap_async(&sync_any).await;
+        assert_eq!(sync_any.get_cloned().unwrap().value, 3);
+        assert_eq!(async_any.get_cloned_async().await.value, 11);
+        assert_eq!(mutex_any.get_cloned().unwrap().value, 4);
+        assert_eq!(async_mutex_any.get_cloned_async().await.value, 4);
+    }
+}
+
+#[cfg(test)]
+mod conversion_tests {
+    use shared_container::{LocalShared, Shared, SharedAny, SharedMutex, SyncAccess};
+
+    #[test]
+    fn test_try_unwrap_requires_unique_handle() {
+        let shared = Shared::new(vec![1, 2]);
+        let weak = shared.downgrade();
+        let other = shared.clone();
+
+        let shared = shared.try_unwrap().unwrap_err();
+        drop(other);
+        assert_eq!(shared.try_unwrap().unwrap(), vec![1, 2]);
+        assert!(weak.upgrade().is_none());
+
+        let mutex = SharedMutex::new(3);
+        assert_eq!(mutex.into_inner().unwrap(), Some(3));
+
+        let local = LocalShared::new(String::from("a"));
+        let other = local.clone();
+        assert_eq!(local.into_inner(), None);
+        assert_eq!(other.into_inner().unwrap(), "a");
+    }
+
+    #[test]
+    fn test_into_inner_races_to_one_winner() {
+        let shared = Shared::new(7);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = shared.clone();
+                std::thread::spawn(move || shared.into_inner().unwrap())
+            })
+            .collect();
+        drop(shared);
+        let values: Vec<_> = handles
+            .into_iter()
+            .filter_map(|h| h.join().unwrap())
+            .collect();
+        assert_eq!(values, vec![7]);
+    }
+
+    #[test]
+    fn test_shared_any_try_unwrap() {
+        let any: SharedAny<i32> = Shared::new(1).into();
+        let other = any.clone();
+        let any = any.try_unwrap().unwrap_err();
+        *other.write().unwrap() += 1;
+        drop(other);
+        assert_eq!(any.try_unwrap().unwrap(), 2);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_shared_into_async_and_back() {
+        use shared_container::AsyncShared;
+
+        let shared = Shared::new(vec![1]);
+        let other = shared.clone();
+        let shared = shared.into_async().unwrap_err();
+        drop(other);
+
+        let async_shared = shared.into_async().unwrap();
+        async_shared.blocking_write().unwrap().push(2);
+        let other = async_shared.clone();
+        let async_shared = async_shared.into_sync().unwrap_err();
+        drop(other);
+
+        let shared = async_shared.into_sync().unwrap();
+        assert_eq!(shared.get_cloned().unwrap(), vec![1, 2]);
+        assert_eq!(AsyncShared::new(5).try_unwrap().unwrap(), 5);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_shared_any_mode_conversion() {
+        use shared_container::AsyncSharedMutex;
+
+        let any: SharedAny<i32> = Shared::new(1).into();
+        let any = any.try_into_async().unwrap();
+        assert!(matches!(any, SharedAny::Async(_)));
+        let any = any.try_into_async().unwrap();
+        let any = any.try_into_sync().unwrap();
+        assert!(matches!(any, SharedAny::Sync(_)));
+        assert_eq!(any.get_cloned().unwrap(), 1);
+
+        let any: SharedAny<i32> = SharedMutex::new(2).into();
+        let any = any.try_into_async().unwrap();
+        assert!(matches!(any, SharedAny::AsyncMutex(_)));
+        assert!(matches!(any.try_into_sync().unwrap(), SharedAny::Mutex(_)));
+
+        // Shared handles are left alone
+        let any: SharedAny<i32> = AsyncSharedMutex::new(3).into();
+        let other = any.clone();
+        let any = any.try_into_sync().unwrap_err();
+        assert!(matches!(any, SharedAny::AsyncMutex(_)));
+        drop(other);
+        assert_eq!(any.into_inner().unwrap(), Some(3));
+    }
+}
+
+#[cfg(test)]
+mod unique_owner_tests {
+    use shared_container::{
+        AccessError, LocalShared, PoisonPolicy, Shared, SharedMutex, SyncAccess,
+    };
+
+    #[test]
+    fn test_get_mut_needs_unique_handle() {
+        let mut shared = Shared::new(1);
+        *shared.get_mut().unwrap() += 1;
+
+        let weak = shared.downgrade();
+        assert_eq!(shared.get_mut().unwrap_err(), AccessError::NotUnique);
+        drop(weak);
+
+        let subscription = shared.subscribe(|_| {});
+        assert_eq!(shared.get_mut().unwrap_err(), AccessError::NotUnique);
+        drop(subscription);
+        assert_eq!(*shared.get_mut().unwrap(), 2);
+
+        let mut local = LocalShared::new(String::from("a"));
+        let other = local.clone();
+        assert_eq!(local.get_mut().unwrap_err(), AccessError::NotUnique);
+        drop(other);
+        local.get_mut().unwrap().push('b');
+        assert_eq!(local.into_inner().unwrap(), "ab");
+    }
+
+    #[test]
+    fn test_make_mut_clones_shared_value() {
+        let mut snapshot = Shared::new(vec![1]);
+        let live = snapshot.clone();
+        snapshot.make_mut().unwrap().push(2);
+        assert_eq!(live.get_cloned().unwrap(), vec![1]);
+        assert_eq!(snapshot.get_cloned().unwrap(), vec![1, 2]);
+
+        // `live` is unique now, so it is written in place
+        let mut live = live;
+        let weak = live.downgrade();
+        drop(weak);
+        live.make_mut().unwrap().push(3);
+        assert_eq!(live.into_inner().unwrap(), Some(vec![1, 3]));
+
+        let mut mutex = SharedMutex::new(1);
+        let other = mutex.clone();
+        *mutex.make_mut().unwrap() = 2;
+        assert_eq!(other.get_cloned().unwrap(), 1);
+        assert_eq!(mutex.into_inner().unwrap(), Some(2));
+
+        let mut local = LocalShared::new(1);
+        let other = local.clone();
+        let guard = other.write().unwrap();
+        assert_eq!(
+            local.make_mut().unwrap_err(),
+            AccessError::BorrowConflict
+        );
+        drop(guard);
+        *local.make_mut().unwrap() = 5;
+        assert_eq!(other.get_cloned().unwrap(), 1);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_unique_access_honours_poisoning() {
+        let poison = |shared: &Shared<i32>| {
+            let other = shared.clone();
+            let _ = std::thread::spawn(move || {
+                let _guard = other.write().unwrap();
+                panic!("writer failed");
+            })
+            .join();
+        };
+
+        let mut shared = Shared::new(1);
+        poison(&shared);
+        assert_eq!(shared.get_mut().unwrap_err(), AccessError::Poisoned);
+        let id = shared.id();
+        let weak = shared.downgrade();
+        let shared = shared.try_unwrap().unwrap_err();
+        // The handle comes back as the same container
+        assert_eq!(shared.id(), id);
+        assert!(weak.upgrade().is_some());
+        shared.clear_poison();
+        assert_eq!(shared.try_unwrap().unwrap(), 1);
+        assert!(weak.upgrade().is_none());
+
+        let shared = Shared::new(2);
+        poison(&shared);
+        let err = shared.into_inner().unwrap_err();
+        assert_ne!(err.info().thread_id(), std::thread::current().id());
+        assert_eq!(err.into_inner(), 2);
+
+        let mutex = SharedMutex::new(4);
+        let other = mutex.clone();
+        let _ = std::thread::spawn(move || other.with_mut(|_| panic!("writer failed"))).join();
+        assert_eq!(mutex.into_inner().unwrap_err().into_inner(), 4);
+
+        let mut shared = Shared::with_poison_policy(3, PoisonPolicy::Ignore);
+        poison(&shared);
+        assert_eq!(*shared.get_mut().unwrap(), 3);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_unique_access() {
+        use shared_container::{AsyncShared, AsyncSharedMutex};
+
+        let mut shared = AsyncShared::new(vec![1]);
+        let other = shared.clone();
+        assert_eq!(shared.get_mut().unwrap_err(), AccessError::NotUnique);
+        let guard = other.blocking_write().unwrap();
+        assert_eq!(shared.make_mut().unwrap_err(), AccessError::WouldBlock);
+        drop(guard);
+        shared.make_mut().unwrap().push(2);
+        assert_eq!(other.blocking_get_cloned().unwrap(), vec![1]);
+        shared.get_mut().unwrap().push(3);
+        assert_eq!(shared.into_inner().unwrap(), vec![1, 2, 3]);
+
+        let mut mutex = AsyncSharedMutex::new(1);
+        *mutex.get_mut().unwrap() += 1;
+        let other = mutex.clone();
+        *mutex.make_mut().unwrap() += 1;
+        assert_eq!(other.try_unwrap().ok(), Some(2));
+        assert_eq!(mutex.into_inner(), Some(3));
+    }
+}
+
+#[cfg(test)]
+mod identity_tests {
+    use shared_container::{ByIdentity, LocalShared, Shared, SharedAny, SharedMutex, SyncAccess};
+    use std::collections::{BTreeSet, HashMap, HashSet};
+
+    #[test]
+    fn test_ptr_eq_and_id() {
+        let a = Shared::new(1);
+        let b = Shared::new(1);
+        assert!(a.ptr_eq(&a.clone()));
+        assert!(!a.ptr_eq(&b));
+        assert_ne!(a.id(), b.id());
+
+        let weak = a.downgrade();
+        assert_eq!(weak.id(), a.id());
+        assert!(weak.ptr_eq(&a.downgrade()));
+        drop(a);
+        assert!(weak.upgrade().is_none());
+
+        let local = LocalShared::new(1);
+        assert_eq!(local.downgrade().id(), local.clone().id());
+
+        let any = SharedAny::from(b.clone());
+        assert_eq!(any.id(), b.id());
+        assert!(any.ptr_eq(&any.clone()));
+        assert_eq!(any.downgrade().id(), b.id());
+    }
+
+    #[test]
+    fn test_counts() {
+        let shared = Shared::new(1);
+        assert_eq!((shared.strong_count(), shared.weak_count()), (1, 0));
+
+        let other = shared.clone();
+        let weak = shared.downgrade();
+        let subscription = shared.subscribe(|_| {});
+        assert_eq!(shared.strong_count(), 2);
+        assert_eq!(weak.weak_count(), 2);
+
+        drop(subscription);
+        drop(other);
+        assert_eq!((weak.strong_count(), weak.weak_count()), (1, 1));
+        drop(shared);
+        assert_eq!(weak.strong_count(), 0);
+
+        let mutex = SharedMutex::new(1);
+        let any = SharedAny::from(mutex.clone());
+        assert_eq!(any.strong_count(), 2);
+        assert_eq!(mutex.downgrade().strong_count(), 2);
+    }
+
+    // Keys hash by address, so the interior mutability clippy sees is irrelevant
+    #[allow(clippy::mutable_key_type)]
+    #[test]
+    fn test_by_identity_keys() {
+        let a = Shared::new(0);
+        let b = Shared::new(0);
+
+        let mut set = HashSet::new();
+        assert!(set.insert(ByIdentity(a.clone())));
+        assert!(!set.insert(ByIdentity(a.clone())));
+        assert!(set.insert(ByIdentity(b.clone())));
+
+        let mut map = HashMap::new();
+        map.insert(ByIdentity(a.downgrade()), "a");
+        *a.write().unwrap() = 1; // keys don't depend on the value
+        assert_eq!(map[&ByIdentity(a.downgrade())], "a");
+
+        let ordered: BTreeSet<_> = [&a, &b, &a].into_iter().cloned().map(ByIdentity).collect();
+        assert_eq!(ordered.len(), 2);
+        assert_eq!(ordered.first().unwrap().id(), a.id().min(b.id()));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_identity() {
+        use shared_container::{AsyncShared, AsyncSharedMutex};
+
+        let shared = AsyncShared::new(1);
+        let weak = shared.downgrade();
+        assert_eq!(weak.id(), shared.id());
+        assert_eq!((shared.strong_count(), shared.weak_count()), (1, 1));
+        assert_eq!(SharedAny::from(shared.clone()).id(), shared.id());
+
+        let mutex = AsyncSharedMutex::new(1);
+        assert!(!SharedAny::from(mutex.clone()).ptr_eq(&SharedAny::from(shared)));
+        assert_eq!(mutex.downgrade().strong_count(), 1);
+    }
+}
 
-            let result = any.write();
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), AccessError::UnsupportedMode);
+#[cfg(test)]
+mod value_trait_tests {
+    use shared_container::{
+        AccessError, LocalShared, PoisonPolicy, Shared, SharedAny, SharedMutex, SyncAccess,
+        ThreadBoundShared,
+    };
+    use std::cmp::Ordering;
+
+    #[derive(Default, PartialEq, Debug)]
+    struct Settings {
+        volume: Shared<u8>,
+        name: Shared<String>,
+    }
+
+    #[test]
+    fn test_default_and_from() {
+        let settings = Settings { volume: 7.into(), ..Default::default() };
+        assert_eq!(settings, Settings { volume: Shared::new(7), name: Shared::default() });
+        assert_ne!(settings, Settings::default());
+
+        assert_eq!(SharedMutex::from(1), SharedMutex::new(1));
+        assert_eq!(LocalShared::<i32>::default(), LocalShared::new(0));
+        assert_eq!(SharedAny::from(Shared::new(0)), SharedAny::default());
+    }
+
+    #[test]
+    fn test_compare_with_itself() {
+        // a mutex can't be locked twice, so this would deadlock if it were
+        let mutex = SharedMutex::new(1);
+        assert_eq!(mutex, mutex.clone());
+
+        let shared = Shared::new(f64::NAN);
+        assert_ne!(shared, shared.clone());
+
+        let local = LocalShared::new(1);
+        assert_eq!(local.try_eq(&local.clone()), Ok(true));
+        let _guard = local.write().unwrap();
+        assert_ne!(local, local.clone());
+        assert_eq!(
+            local.try_eq(&local.clone()),
+            Err(AccessError::BorrowConflict)
+        );
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut values = [Shared::new(3), Shared::new(1), Shared::new(2)];
+        values.sort_by(|a, b| a.try_cmp(b).unwrap());
+        let sorted: Vec<_> = values.iter().map(|v| v.get_cloned().unwrap()).collect();
+        assert_eq!(sorted, [1, 2, 3]);
+        assert!(SharedAny::from(Shared::new(1)) < SharedAny::from(SharedMutex::new(2)));
+        assert_eq!(
+            SharedMutex::new(2).try_cmp(&SharedMutex::new(1)),
+            Ok(Ordering::Greater)
+        );
+
+        let nan = Shared::new(f64::NAN);
+        assert_eq!(nan.try_partial_cmp(&Shared::new(1.0)), Ok(None));
+        assert_eq!(nan.partial_cmp(&Shared::new(1.0)), None);
+    }
+
+    fn poison(shared: &Shared<i32>) {
+        let other = shared.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = other.write().unwrap();
+            panic!("poison");
+        })
+        .join();
+    }
+
+    #[test]
+    fn test_unreadable_values_are_unequal() {
+        let poisoned = Shared::new(1);
+        poison(&poisoned);
+        assert_ne!(poisoned, Shared::new(1));
+        assert_ne!(poisoned, poisoned.clone());
+        assert_eq!(poisoned.partial_cmp(&Shared::new(1)), None);
+        assert_eq!(poisoned.try_eq(&poisoned.clone()), Err(AccessError::Poisoned));
+        assert_eq!(poisoned.try_cmp(&Shared::new(1)), Err(AccessError::Poisoned));
+        assert_eq!(poisoned.to_string(), "<lock poisoned by panic>");
+
+        let ignored = Shared::with_poison_policy(1, PoisonPolicy::Ignore);
+        poison(&ignored);
+        assert_eq!(ignored, Shared::new(1));
+        assert_eq!(ignored.to_string(), "1");
+
+        let bound = ThreadBoundShared::new(1);
+        let remote = bound.clone();
+        std::thread::spawn(move || {
+            assert_ne!(remote, ThreadBoundShared::new(1));
+            assert_eq!(remote.try_eq(&remote.clone()), Err(AccessError::WrongThread));
+        })
+        .join()
+        .unwrap();
+        assert_eq!(bound, ThreadBoundShared::new(1));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Shared::new("hi").to_string(), "hi");
+        assert_eq!(format!("{:>3}", SharedMutex::new(7)), "  7");
+
+        let local = LocalShared::new(1);
+        let _guard = local.write().unwrap();
+        assert_eq!(local.to_string(), "<borrow conflict: lock already held>");
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_display() {
+        use shared_container::{AsyncAccess, AsyncShared};
+
+        let shared = AsyncShared::new(5);
+        assert_eq!(shared.to_string(), "5");
+        // inside a runtime a held lock isn't waited for
+        let _guard = shared.write_async().await;
+        assert_eq!(
+            SharedAny::from(shared.clone()).to_string(),
+            "<lock is busy: operation would block>"
+        );
+    }
+
+    #[cfg(feature = "async-cmp")]
+    #[tokio::test]
+    async fn test_async_compare_inside_runtime() {
+        use shared_container::{AsyncAccess, AsyncShared, AsyncSharedMutex};
 
-            let result = any.get_cloned();
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), AccessError::UnsupportedMode);
+        let a = AsyncShared::new(1);
+        assert_eq!(a, AsyncShared::new(1));
+
+        // a held lock isn't waited for inside a runtime
+        let guard = a.write_async().await;
+        assert_ne!(a, AsyncShared::new(1));
+        assert_ne!(a, a.clone());
+        assert_eq!(a.try_eq(&a.clone()), Err(AccessError::WouldBlock));
+        drop(guard);
+
+        let mutex = AsyncSharedMutex::new(1);
+        assert_eq!(mutex, mutex.clone());
+        assert!(mutex < AsyncSharedMutex::new(2));
+        assert_eq!(
+            mutex.try_cmp(&AsyncSharedMutex::new(0)),
+            Ok(Ordering::Greater)
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_shared_any_async_compare_needs_feature() {
+        use shared_container::AsyncShared;
+
+        let any = SharedAny::from(AsyncShared::new(1));
+        let sync = SharedAny::from(Shared::new(1));
+        assert_eq!(any.try_eq(&sync), Ok(true));
+        assert_eq!(any == sync, cfg!(feature = "async-cmp"));
+        assert_eq!(any.partial_cmp(&any).is_some(), cfg!(feature = "async-cmp"));
+        assert_eq!(sync, SharedAny::from(SharedMutex::new(1)));
+    }
+}
+
+#[cfg(test)]
+mod debug_tests {
+    use shared_container::{Shared, SharedAny, SharedMutex, SyncAccess};
+
+    #[test]
+    fn test_shared_debug() {
+        let shared = Shared::new(vec![1]);
+        assert_eq!(format!("{:?}", shared), "Shared { value: [1] }");
+
+        let weak = shared.downgrade();
+        let _guard = shared.write().unwrap();
+        assert_eq!(
+            format!("{:?}", shared.clone()),
+            "Shared { value: <busy>, strong: 2, weak: 1 }"
+        );
+        drop(weak);
+    }
+
+    #[test]
+    fn test_shared_debug_poisoned() {
+        let shared = Shared::new(1);
+        let other = shared.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = other.write().unwrap();
+            panic!("poison");
+        })
+        .join();
+        assert_eq!(
+            format!("{:?}", shared),
+            "Shared { value: <poisoned>, strong: 1, weak: 0 }"
+        );
+    }
+
+    #[test]
+    fn test_debug_does_not_block() {
+        let shared = Shared::new(1);
+        let (locked, release) = std::sync::mpsc::channel();
+        let (done, finished) = std::sync::mpsc::channel::<()>();
+        let other = shared.clone();
+        let holder = std::thread::spawn(move || {
+            let _guard = other.write().unwrap();
+            locked.send(()).unwrap();
+            finished.recv().unwrap();
         });
+        release.recv().unwrap();
+        assert!(format!("{:?}", shared).contains("<busy>"));
+        done.send(()).unwrap();
+        holder.join().unwrap();
+        assert_eq!(format!("{:?}", shared), "Shared { value: 1 }");
+    }
+
+    #[test]
+    fn test_debug_with_waiting_writer() {
+        let shared = Shared::new(1);
+        let reader = shared.read().unwrap();
+        let other = shared.clone();
+        let writer = std::thread::spawn(move || *other.write().unwrap() = 2);
+        // Once the writer waits, new readers queue behind it
+        while shared.try_read().is_ok() {
+            std::thread::yield_now();
+        }
+        assert!(format!("{:?}", shared).contains("<busy>"));
+        drop(reader);
+        writer.join().unwrap();
+        assert_eq!(format!("{:?}", shared), "Shared { value: 2 }");
+    }
+
+    #[test]
+    fn test_shared_any_debug() {
+        let mutex = SharedMutex::new(1);
+        let any = SharedAny::from(mutex.clone());
+        assert_eq!(format!("{:?}", any), "Mutex(SharedMutex { value: 1 })");
+        let _guard = mutex.read().unwrap();
+        assert_eq!(
+            format!("{:?}", any),
+            "Mutex(SharedMutex { value: <busy>, strong: 2, weak: 0 })"
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_debug() {
+        use shared_container::{AsyncAccess, AsyncShared, AsyncSharedMutex};
+
+        let shared = AsyncShared::new(1);
+        assert_eq!(format!("{:?}", shared), "AsyncShared { value: 1 }");
+        let guard = shared.write_async().await;
+        assert_eq!(
+            format!("{:?}", SharedAny::from(shared.clone())),
+            "Async(AsyncShared { value: <busy>, strong: 2, weak: 0 })"
+        );
+        drop(guard);
+
+        let mutex = AsyncSharedMutex::new(1);
+        let _guard = mutex.lock().await;
+        assert_eq!(
+            format!("{:?}", mutex),
+            "AsyncSharedMutex { value: <busy>, strong: 1, weak: 0 }"
+        );
     }
 }
@@ -16,4 +16,8 @@ cargo test --no-default-features --features wasm-sync
 echo "Running tests with force-wasm-impl feature..."
 cargo test --no-default-features --features force-wasm-impl
 
+# Run tests with the optional lock backends
+echo "Running tests with lock backend features..."
+cargo test --features backend-parking-lot,backend-mutex,backend-spin
+
 echo "All tests completed!"
\ No newline at end of file
@@ -3,6 +3,7 @@
     not(feature = "tokio-sync"),
     not(feature = "wasm-sync")
 ))]
+#![allow(deprecated)]
 
 use shared_container::SharedContainer;
 
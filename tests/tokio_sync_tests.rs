@@ -1,4 +1,5 @@
 #![cfg(feature = "tokio-sync")]
+#![allow(deprecated)]
 
 use shared_container::SharedContainer;
 use tokio::runtime::Runtime;
@@ -1,4 +1,5 @@
 #![cfg(any(feature = "wasm-sync", feature = "force-wasm-impl"))]
+#![allow(deprecated)]
 
 use shared_container::SharedContainer;
 